@@ -1,104 +1,101 @@
 use std::rc::Rc;
-use crate::data::{Coord, Node};
-use crate::std_ext::RcBreaker;
+use crate::data::Coord;
+use crate::data::cost::Cost;
+use crate::algos::node_arena::{NodeArena, Membership};
 use crate::data::diagonal::Diagonal;
-use crate::data::heuristic::Heuristic;
-use crate::algos::{Algorithm, AlgoStatus, CostCalc};
+use crate::data::cost_model::CostModel;
+use crate::data::heuristic::{Heuristic, Combination};
+use crate::algos::{Algorithm, AlgoStatus, AlgoEvent, Frontier, Path, CostCalc, path_cost, optimal_cost};
 use crate::algos::AlgoStatus::*;
 
+pub const DEFAULT_WEIGHT: f32 = 2.;
+
 pub struct Astar {
+    start: Coord,
+    weight: Option<f32>,
+    optimal_cost: Option<Cost>,
     diagonal: Diagonal,
+    cost_model: CostModel,
     width: i32,
     height: i32,
-    open_nodes: Vec<Rc<Node>>,
-    closed_nodes: Vec<Rc<Node>>,
+    nodes: NodeArena,
     cost_calc: Rc<Box<CostCalc>>,
     ends: Vec<Coord>,
     heuristic: Heuristic,
+    combination: Combination,
     status: AlgoStatus,
 }
 
 impl Astar {
-    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal, heuristic: Heuristic) -> Astar {
+    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal, cost_model: CostModel, heuristic: Heuristic, combination: Combination) -> Astar {
         let rc_cost_calc = Rc::new(cost_calc);
+        let mut nodes = NodeArena::new(width, height);
+        nodes.open(start, None, Cost::ZERO, Cost::ZERO, Cost::ZERO);
         Astar {
+            start,
+            weight: None,
+            optimal_cost: None,
             width,
             height,
             diagonal,
-            open_nodes: vec![Rc::new(start.into())],
-            closed_nodes: vec![],
+            cost_model,
+            nodes,
             cost_calc: rc_cost_calc.clone(),
             ends,
             heuristic,
-            status: AlgoStatus::InProgress((vec![], vec![])),
+            combination,
+            status: AlgoStatus::InProgress(vec![]),
         }
     }
+
+    //f = g + weight * h, higher weights find a path faster but it may not be the cheapest
+    pub fn new_weighted(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal, cost_model: CostModel, heuristic: Heuristic, combination: Combination, weight: f32) -> Astar {
+        let mut astar = Astar::new_fixed_target(start, ends, cost_calc, width, height, diagonal, cost_model, heuristic, combination);
+        astar.weight = Some(weight);
+        astar.optimal_cost = optimal_cost(start, &astar.ends, astar.cost_calc.clone(), width, height, diagonal, cost_model);
+        return astar;
+    }
 }
 
 impl Astar {
     fn process_once(&mut self) {
-        if self.open_nodes.is_empty() {
-            self.status = NoPath;
-            return;
-        }
-
-        let (idx, _) = self.open_nodes.iter()
-            .enumerate()
-            .min_by(|&lhs: &(usize, &Rc<Node>), &rhs: &(usize, &Rc<Node>)| lhs.1.f.cmp(&rhs.1.f))
-            .unwrap();
-
-        let current_node = self.open_nodes.remove(idx);
-
-        if self.ends.contains(&current_node.xy) {
-            let mut path = vec![];
-            let mut current = Some(current_node);
-            while current.is_some() {
-                let current1 = current.clone().unwrap();
-                let current2 = current.clone().unwrap();
-                path.push(current1);
-                current = current2.parent.clone();
+        let current = match self.nodes.pop() {
+            Some(xy) => xy,
+            None => {
+                self.nodes.record(AlgoEvent::NoPath);
+                self.status = NoPath;
+                return;
             }
-            let closed = self.closed_nodes.iter().map(|item| item.xy.clone()).collect();
-            let result: Vec<Coord> = path.iter()
-                .rev()
-                .map(|item| item.xy)
-                .collect();
-            self.status = Found(result, closed);
+        };
+
+        if self.ends.contains(&current) {
+            let path = Path::Tiles(self.nodes.path_to(current));
+            self.nodes.record(AlgoEvent::Finished(path.clone()));
+            self.status = Found(path, vec![Frontier::new(vec![], self.nodes.closed_coords())]);
             return;
         }
 
-        self.closed_nodes.push(current_node.clone());
-
-        let mut children = vec![];
-        for offset in self.diagonal.get_neighbours(self.cost_calc.clone(), current_node.clone().xy).iter() {
-            let new_pos: Coord =  offset.clone();
-
+        let current_g = self.nodes.get(current).g;
+        for new_pos in self.diagonal.get_neighbours(self.cost_calc.clone(), current) {
             if new_pos.is_out_of_bounds(self.width, self.height) { continue; }
 
             if (self.cost_calc)(new_pos) < 0 { continue; }
 
-            let node = Node::new(new_pos, Some(current_node.clone()));
-
-            children.push(node);
-        }
-
-        for mut child in children {
-            if self.open_nodes.contains_item(&child) { continue; }
-            if self.closed_nodes.contains_item(&child) { continue; }
-
-            child.g = current_node.clone().g + 1;
-            child.h = self.heuristic.calc_multiple(&child.xy, &self.ends);
-            child.f = child.g + child.h + ((self.cost_calc)(child.xy) * 5);
+            let g = current_g + self.cost_model.step_cost(self.cost_calc.as_ref(), current, new_pos);
+            let existing = self.nodes.get(new_pos);
+            //An admissible heuristic that isn't consistent can close a node before its cheapest route is found,
+            //so it's opened again, weighted A* skips this as it's trading path cost for speed anyway
+            if existing.membership == Membership::Closed && self.weight.is_some() { continue; }
+            if existing.g <= g { continue; }
 
-            let is_larger = self.open_nodes.iter()
-                .any(|item| &child == item && child.f > item.f);
-            if is_larger { continue; }
+            let h = self.heuristic.calc_multiple(&self.start, &new_pos, &self.ends, self.combination);
+            let weighted_h = match self.weight {
+                Some(weight) => h.scale(weight),
+                None => h
+            };
 
-            self.open_nodes.push(Rc::new(child));
+            self.nodes.open(new_pos, Some(current), g, h, g + weighted_h);
         }
-
-        self.status = InProgress((self.open_nodes.iter().map(|node| node.xy.clone()).collect(),
-                                  self.closed_nodes.iter().map(|node| node.xy.clone()).collect()));
     }
 }
 
@@ -115,4 +112,31 @@ impl Algorithm for Astar {
     fn get_data(&self) -> &AlgoStatus {
         return &self.status;
     }
+
+    fn take_events(&mut self) -> Vec<AlgoEvent> {
+        return self.nodes.take_events();
+    }
+
+    fn get_weight(&self) -> Option<f32> {
+        return self.weight;
+    }
+
+    fn set_weight(&mut self, weight: f32) {
+        if self.weight.is_some() {
+            self.weight = Some(weight);
+            self.nodes = NodeArena::new(self.width, self.height);
+            self.nodes.record(AlgoEvent::Restarted);
+            self.nodes.open(self.start, None, Cost::ZERO, Cost::ZERO, Cost::ZERO);
+            self.status = AlgoStatus::InProgress(vec![]);
+        }
+    }
+
+    fn get_cost_ratio(&self) -> Option<f32> {
+        if let (Found(path, _), Some(optimal)) = (&self.status, self.optimal_cost) {
+            if optimal > Cost::ZERO {
+                return Some((path_cost(self.cost_model, self.cost_calc.as_ref(), path.coords()).as_tiles() / optimal.as_tiles()) as f32);
+            }
+        }
+        return None;
+    }
 }
\ No newline at end of file
@@ -0,0 +1,85 @@
+use std::rc::Rc;
+use crate::data::Coord;
+use crate::data::cost::Cost;
+use crate::data::diagonal::Diagonal;
+use crate::algos::node_arena::{NodeArena, Membership};
+use crate::algos::{Algorithm, AlgoStatus, AlgoEvent, Frontier, Path, CostCalc};
+use crate::algos::AlgoStatus::*;
+
+//Tile costs are only used to find walls, every step is treated as the same cost
+//g is the number of steps taken, so the arena hands tiles back in the order they were opened
+pub struct Bfs {
+    diagonal: Diagonal,
+    width: i32,
+    height: i32,
+    nodes: NodeArena,
+    cost_calc: Rc<Box<CostCalc>>,
+    ends: Vec<Coord>,
+    status: AlgoStatus,
+}
+
+impl Bfs {
+    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal) -> Bfs {
+        let mut nodes = NodeArena::new(width, height);
+        nodes.open(start, None, Cost::ZERO, Cost::ZERO, Cost::ZERO);
+        Bfs {
+            width,
+            height,
+            diagonal,
+            nodes,
+            cost_calc: Rc::new(cost_calc),
+            ends,
+            status: AlgoStatus::InProgress(vec![]),
+        }
+    }
+}
+
+impl Bfs {
+    fn process_once(&mut self) {
+        let current = match self.nodes.pop() {
+            Some(xy) => xy,
+            None => {
+                self.nodes.record(AlgoEvent::NoPath);
+                self.status = NoPath;
+                return;
+            }
+        };
+
+        if self.ends.contains(&current) {
+            let path = Path::Tiles(self.nodes.path_to(current));
+            self.nodes.record(AlgoEvent::Finished(path.clone()));
+            self.status = Found(path, vec![Frontier::new(vec![], self.nodes.closed_coords())]);
+            return;
+        }
+
+        let g = self.nodes.get(current).g + Cost::tiles(1);
+        for new_pos in self.diagonal.get_neighbours(self.cost_calc.clone(), current) {
+            if new_pos.is_out_of_bounds(self.width, self.height) { continue; }
+
+            if (self.cost_calc)(new_pos) < 0 { continue; }
+
+            if self.nodes.get(new_pos).membership != Membership::Unvisited { continue; }
+
+            self.nodes.open(new_pos, Some(current), g, Cost::ZERO, g);
+        }
+    }
+}
+
+impl Algorithm for Bfs {
+    fn tick(&mut self) {
+        match self.status {
+            AlgoStatus::InProgress(_) => self.process_once(),
+            _ => {
+                //do nothing
+            }
+        }
+    }
+
+    fn get_data(&self) -> &AlgoStatus {
+        return &self.status;
+    }
+
+    fn take_events(&mut self) -> Vec<AlgoEvent> {
+        return self.nodes.take_events();
+    }
+}
@@ -0,0 +1,169 @@
+use std::rc::Rc;
+use crate::data::Coord;
+use crate::data::diagonal::Diagonal;
+use crate::data::cost_model::CostModel;
+use crate::data::heuristic::{Heuristic, Combination};
+use crate::data::cost::Cost;
+use crate::algos::node_arena::{NodeArena, Membership};
+use crate::algos::{Algorithm, AlgoStatus, AlgoEvent, Frontier, Path, CostCalc};
+use crate::algos::AlgoStatus::*;
+
+//One direction of the search, `targets` are where this half is heading
+struct Search {
+    nodes: NodeArena,
+    //where the search began, the first end for the backward search
+    origin: Coord,
+    targets: Vec<Coord>,
+}
+
+impl Search {
+    fn new(frontier: usize, starts: Vec<Coord>, targets: Vec<Coord>, width: i32, height: i32) -> Search {
+        let mut nodes = NodeArena::for_frontier(width, height, frontier);
+        for &start in &starts {
+            nodes.open(start, None, Cost::ZERO, Cost::ZERO, Cost::ZERO);
+        }
+        Search {
+            nodes,
+            origin: starts[0],
+            targets,
+        }
+    }
+
+    fn has_reached(&self, xy: Coord) -> bool {
+        self.nodes.get(xy).membership != Membership::Unvisited
+    }
+}
+
+//Searches forwards from the start and backwards from every end, alternating each tick
+//Whenever the searches meet the path through the meeting tile is kept if it's the cheapest so far,
+//it's the cheapest path once the search being processed can't find anything cheaper
+//With the heuristic set to always 0 this is a bidirectional Dijkstra
+pub struct Bidirectional {
+    diagonal: Diagonal,
+    cost_model: CostModel,
+    width: i32,
+    height: i32,
+    searches: [Search; 2],
+    next_search: usize,
+    //cost of the cheapest path found so far and the tile where the searches met on it
+    best: Option<(Cost, Coord)>,
+    cost_calc: Rc<Box<CostCalc>>,
+    heuristic: Heuristic,
+    combination: Combination,
+    status: AlgoStatus,
+}
+
+const FORWARD: usize = 0;
+const BACKWARD: usize = 1;
+
+impl Bidirectional {
+    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal, cost_model: CostModel, heuristic: Heuristic, combination: Combination) -> Bidirectional {
+        Bidirectional {
+            width,
+            height,
+            diagonal,
+            cost_model,
+            searches: [Search::new(FORWARD, vec![start], ends.clone(), width, height), Search::new(BACKWARD, ends, vec![start], width, height)],
+            next_search: FORWARD,
+            best: None,
+            cost_calc: Rc::new(cost_calc),
+            heuristic,
+            combination,
+            status: AlgoStatus::InProgress(vec![]),
+        }
+    }
+}
+
+impl Bidirectional {
+    //Keeps the path through `xy` if it's the cheapest yet, both searches must have reached it
+    fn meet(&mut self, xy: Coord) {
+        let cost = self.searches[FORWARD].nodes.get(xy).g + self.searches[BACKWARD].nodes.get(xy).g;
+        if self.best.is_none_or(|(best, _)| cost < best) {
+            self.best = Some((cost, xy));
+        }
+    }
+
+    //The outcome is recorded with the forward search's events
+    fn finish(&mut self) {
+        self.status = match self.best {
+            Some((_, xy)) => {
+                let mut path = self.searches[FORWARD].nodes.path_to(xy);
+                let mut backward = self.searches[BACKWARD].nodes.path_to(xy);
+                backward.reverse();
+                path.extend(backward.into_iter().skip(1));
+                let path = Path::Tiles(path);
+                self.searches[FORWARD].nodes.record(AlgoEvent::Finished(path.clone()));
+                Found(path, self.searches.iter().map(|search| Frontier::new(vec![], search.nodes.closed_coords())).collect())
+            }
+            None => {
+                self.searches[FORWARD].nodes.record(AlgoEvent::NoPath);
+                NoPath
+            }
+        };
+    }
+
+    fn process_once(&mut self) {
+        let idx = self.next_search;
+        let other = if idx == FORWARD { BACKWARD } else { FORWARD };
+        self.next_search = other;
+
+        //Every path this search hasn't found yet costs at least the lowest f left to it
+        match (self.searches[idx].nodes.lowest_f(), self.best) {
+            (Some(f), Some((best, _))) if best <= f => return self.finish(),
+            (Some(_), _) => {}
+            (None, _) => return self.finish(),
+        }
+        let current = self.searches[idx].nodes.pop().expect("Checked there's an open tile");
+
+        if self.searches[other].has_reached(current) {
+            self.meet(current);
+        }
+
+        let current_g = self.searches[idx].nodes.get(current).g;
+        for new_pos in self.diagonal.get_neighbours(self.cost_calc.clone(), current) {
+            if new_pos.is_out_of_bounds(self.width, self.height) { continue; }
+
+            if (self.cost_calc)(new_pos) < 0 { continue; }
+
+            let search = &mut self.searches[idx];
+            let existing = search.nodes.get(new_pos);
+            if existing.membership == Membership::Closed { continue; }
+
+            //The backward search walks moves in reverse so it pays to enter the tile it came from
+            let g = current_g + if idx == FORWARD {
+                self.cost_model.step_cost(self.cost_calc.as_ref(), current, new_pos)
+            } else {
+                self.cost_model.step_cost(self.cost_calc.as_ref(), new_pos, current)
+            };
+            if existing.g <= g { continue; }
+
+            let h = self.heuristic.calc_multiple(&search.origin, &new_pos, &search.targets, self.combination);
+            search.nodes.open(new_pos, Some(current), g, h, g + h);
+            if self.searches[other].has_reached(new_pos) {
+                self.meet(new_pos);
+            }
+        }
+    }
+}
+
+impl Algorithm for Bidirectional {
+    fn tick(&mut self) {
+        match self.status {
+            AlgoStatus::InProgress(_) => self.process_once(),
+            _ => {
+                //do nothing
+            }
+        }
+    }
+
+    fn get_data(&self) -> &AlgoStatus {
+        return &self.status;
+    }
+
+    //Only one search runs each tick so the order the two are taken in doesn't matter
+    fn take_events(&mut self) -> Vec<AlgoEvent> {
+        let mut events = self.searches[FORWARD].nodes.take_events();
+        events.extend(self.searches[BACKWARD].nodes.take_events());
+        return events;
+    }
+}
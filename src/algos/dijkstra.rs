@@ -1,8 +1,10 @@
 use std::rc::Rc;
-use crate::data::{Coord, Node};
+use crate::data::Coord;
+use crate::data::cost::Cost;
 use crate::data::diagonal::Diagonal;
-use crate::std_ext::RcBreaker;
-use crate::algos::{Algorithm, AlgoStatus};
+use crate::data::cost_model::CostModel;
+use crate::algos::node_arena::{NodeArena, Membership};
+use crate::algos::{Algorithm, AlgoStatus, AlgoEvent, Frontier, Path};
 use crate::algos::AlgoStatus::*;
 
 pub type CostCalc = dyn Fn(Coord) -> i32;
@@ -10,97 +12,66 @@ pub type IsValidEnd = dyn Fn(Coord) -> bool;
 
 pub struct Dijkstra {
     diagonal: Diagonal,
+    cost_model: CostModel,
     width: i32,
     height: i32,
-    open_nodes: Vec<Rc<Node>>,
-    closed_nodes: Vec<Rc<Node>>,
+    nodes: NodeArena,
     cost_calc: Rc<Box<CostCalc>>,
     is_valid_end: Box<IsValidEnd>,
     status: AlgoStatus,
 }
 
 impl Dijkstra {
-    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal) -> Dijkstra {
+    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal, cost_model: CostModel) -> Dijkstra {
         let end_clone = ends.clone();
         let rc_cost_calc = Rc::new(cost_calc);
+        let mut nodes = NodeArena::new(width, height);
+        nodes.open(start, None, Cost::ZERO, Cost::ZERO, Cost::ZERO);
         Dijkstra {
             width,
             height,
             diagonal,
-            open_nodes: vec![Rc::new(start.into())],
-            closed_nodes: vec![],
+            cost_model,
+            nodes,
             cost_calc: rc_cost_calc.clone(),
             is_valid_end: Box::new(move |xy| end_clone.contains(&xy)),
-            status: AlgoStatus::InProgress((vec![], vec![])),
+            status: AlgoStatus::InProgress(vec![]),
         }
     }
 }
 
 impl Dijkstra {
     fn process_once(&mut self) {
-        if self.open_nodes.is_empty() {
-            self.status = NoPath;
-            return;
-        }
-
-        let (idx, _) = self.open_nodes.iter()
-            .enumerate()
-            .min_by(|&lhs: &(usize, &Rc<Node>), &rhs: &(usize, &Rc<Node>)| lhs.1.f.cmp(&rhs.1.f))
-            .unwrap();
-
-        let current_node = self.open_nodes.remove(idx);
-
-        if (self.is_valid_end)(current_node.xy) {
-            let mut path = vec![];
-            let mut current = Some(current_node);
-            while current.is_some() {
-                let current1 = current.clone().unwrap();
-                let current2 = current.clone().unwrap();
-                path.push(current1);
-                current = current2.parent.clone();
+        let current = match self.nodes.pop() {
+            Some(xy) => xy,
+            None => {
+                self.nodes.record(AlgoEvent::NoPath);
+                self.status = NoPath;
+                return;
             }
-            let closed = self.closed_nodes.iter().map(|item| item.xy.clone()).collect();
-            let result: Vec<Coord> = path.iter()
-                .rev()
-                .map(|item| item.xy)
-                .collect();
+        };
 
-            self.status = Found(result, closed);
+        if (self.is_valid_end)(current) {
+            let path = Path::Tiles(self.nodes.path_to(current));
+            self.nodes.record(AlgoEvent::Finished(path.clone()));
+            self.status = Found(path, vec![Frontier::new(vec![], self.nodes.closed_coords())]);
             return;
         }
 
-        self.closed_nodes.push(current_node.clone());
-
-        let mut children = vec![];
-        for offset in self.diagonal.get_neighbours(self.cost_calc.clone(), current_node.clone().xy).iter() {
-            let new_pos: Coord =  offset.clone();
-
+        let current_g = self.nodes.get(current).g;
+        for new_pos in self.diagonal.get_neighbours(self.cost_calc.clone(), current) {
             if new_pos.is_out_of_bounds(self.width, self.height) { continue; }
 
             if (self.cost_calc)(new_pos) < 0 { continue; }
 
-            let node = Node::new(new_pos, Some(current_node.clone()));
+            let existing = self.nodes.get(new_pos);
+            if existing.membership == Membership::Closed { continue; }
 
-            children.push(node);
-        }
-
-        for mut child in children {
-            if self.open_nodes.contains_item(&child) { continue; }
-            if self.closed_nodes.contains_item(&child) { continue; }
-
-            child.g = current_node.clone().g + 1;
-            child.h = 0;
-            child.f = child.g + child.h + ((self.cost_calc)(child.xy) * 5);
-
-            let is_larger = self.open_nodes.iter()
-                .any(|item| &child == item && child.f > item.f);
-            if is_larger { continue; }
+            let g = current_g + self.cost_model.step_cost(self.cost_calc.as_ref(), current, new_pos);
+            if existing.g <= g { continue; }
 
-            self.open_nodes.push(Rc::new(child));
+            self.nodes.open(new_pos, Some(current), g, Cost::ZERO, g);
         }
-
-        self.status = InProgress((self.open_nodes.iter().map(|node| node.xy.clone()).collect(),
-                                  self.closed_nodes.iter().map(|node| node.xy.clone()).collect()));
     }
 }
 
@@ -117,4 +88,8 @@ impl Algorithm for Dijkstra {
     fn get_data(&self) -> &AlgoStatus {
         return &self.status;
     }
+
+    fn take_events(&mut self) -> Vec<AlgoEvent> {
+        return self.nodes.take_events();
+    }
 }
\ No newline at end of file
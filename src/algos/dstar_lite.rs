@@ -0,0 +1,236 @@
+use std::rc::Rc;
+use crate::data::Coord;
+use crate::data::cost::Cost;
+use crate::data::diagonal::Diagonal;
+use crate::data::cost_model::CostModel;
+use crate::data::heuristic::Heuristic;
+use crate::algos::{Algorithm, AlgoStatus, AlgoEvent, Path, CostCalc};
+use crate::algos::AlgoStatus::*;
+
+const INFINITE: Cost = Cost::INFINITE;
+
+type Key = (Cost, Cost);
+
+//D* Lite, searches backwards from the ends to the start
+//When a tile cost changes only the nodes affected by it are updated rather than starting again
+//Tiles with a key are reported as open, shown with the neighbour their rhs came from as the parent
+pub struct DStarLite {
+    diagonal: Diagonal,
+    cost_model: CostModel,
+    width: i32,
+    height: i32,
+    start: Coord,
+    ends: Vec<Coord>,
+    g: Vec<Vec<Cost>>,
+    rhs: Vec<Vec<Cost>>,
+    expanded: Vec<Vec<bool>>,
+    open_nodes: Vec<(Coord, Key)>,
+    cost_calc: Rc<Box<CostCalc>>,
+    heuristic: Heuristic,
+    status: AlgoStatus,
+    events: Vec<AlgoEvent>,
+}
+
+impl DStarLite {
+    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal, cost_model: CostModel, heuristic: Heuristic) -> DStarLite {
+        let mut algo = DStarLite {
+            width,
+            height,
+            diagonal,
+            cost_model,
+            start,
+            ends: ends.clone(),
+            g: vec![vec![INFINITE; height as usize]; width as usize],
+            rhs: vec![vec![INFINITE; height as usize]; width as usize],
+            expanded: vec![vec![false; height as usize]; width as usize],
+            open_nodes: vec![],
+            cost_calc: Rc::new(cost_calc),
+            heuristic,
+            status: AlgoStatus::InProgress(vec![]),
+            events: vec![],
+        };
+        for end in ends {
+            algo.rhs[end.x as usize][end.y as usize] = Cost::ZERO;
+            let key = algo.calc_key(end);
+            algo.open_nodes.push((end, key));
+            algo.events.push(AlgoEvent::Opened(0, end, None, Cost::ZERO, algo.h(end), key.0));
+        }
+        return algo;
+    }
+}
+
+impl DStarLite {
+    fn g(&self, xy: Coord) -> Cost {
+        self.g[xy.x as usize][xy.y as usize]
+    }
+
+    fn rhs(&self, xy: Coord) -> Cost {
+        self.rhs[xy.x as usize][xy.y as usize]
+    }
+
+    fn h(&self, xy: Coord) -> Cost {
+        self.heuristic.calc_fixed(&self.ends[0], &xy, &self.start)
+    }
+
+    fn calc_key(&self, xy: Coord) -> Key {
+        let min = self.g(xy).min(self.rhs(xy));
+        if min >= INFINITE {
+            return (INFINITE, INFINITE);
+        }
+        return (min + self.h(xy), min);
+    }
+
+    fn is_wall(&self, xy: Coord) -> bool {
+        xy.is_out_of_bounds(self.width, self.height) || (self.cost_calc)(xy) < 0
+    }
+
+    fn neighbours(&self, xy: Coord) -> Vec<Coord> {
+        return self.diagonal.get_neighbours(self.cost_calc.clone(), xy).into_iter()
+            .filter(|next| !next.is_out_of_bounds(self.width, self.height))
+            .collect();
+    }
+
+    fn edge_cost(&self, from: Coord, to: Coord) -> Cost {
+        if self.is_wall(from) || self.is_wall(to) {
+            return INFINITE;
+        }
+        return self.cost_model.step_cost(self.cost_calc.as_ref(), from, to);
+    }
+
+    fn update_vertex(&mut self, xy: Coord) {
+        if xy.is_out_of_bounds(self.width, self.height) {
+            return;
+        }
+        let mut parent = None;
+        if !self.ends.contains(&xy) {
+            let best = self.neighbours(xy).into_iter()
+                .filter_map(|next| {
+                    let cost = self.edge_cost(xy, next);
+                    if cost >= INFINITE || self.g(next) >= INFINITE { None } else { Some((cost + self.g(next), next)) }
+                })
+                .min_by_key(|&(rhs, _)| rhs);
+            self.rhs[xy.x as usize][xy.y as usize] = best.map_or(INFINITE, |(rhs, _)| rhs);
+            parent = best.map(|(_, next)| next);
+        }
+        let old_key = self.open_nodes.iter().find(|(node, _)| node == &xy).map(|(_, key)| *key);
+        self.open_nodes.retain(|(node, _)| node != &xy);
+        if self.g(xy) != self.rhs(xy) {
+            let key = self.calc_key(xy);
+            self.open_nodes.push((xy, key));
+            match (old_key, parent) {
+                (Some(old_key), _) if old_key == key => {}
+                (Some(_), Some(parent)) => self.events.push(AlgoEvent::ParentChanged(0, xy, parent, key.1, key.0)),
+                _ => self.events.push(AlgoEvent::Opened(0, xy, parent, key.1, self.h(xy), key.0)),
+            }
+        } else if old_key.is_some() {
+            //made consistent without being expanded, it's shown as it was before it was opened
+            let event = if self.expanded[xy.x as usize][xy.y as usize] { AlgoEvent::Closed(0, xy) } else { AlgoEvent::Dropped(0, xy) };
+            self.events.push(event);
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        let start_key = self.calc_key(self.start);
+        let top_key = self.open_nodes.iter().map(|(_, key)| *key).min();
+        return match top_key {
+            Some(key) => key >= start_key && self.rhs(self.start) == self.g(self.start),
+            None => true
+        };
+    }
+
+    fn process_once(&mut self) {
+        if self.is_complete() {
+            self.finish();
+            return;
+        }
+
+        let (idx, _) = self.open_nodes.iter()
+            .enumerate()
+            .min_by(|lhs, rhs| (lhs.1).1.cmp(&(rhs.1).1))
+            .unwrap();
+        let (current, _) = self.open_nodes.remove(idx);
+        self.expanded[current.x as usize][current.y as usize] = true;
+        self.events.push(AlgoEvent::Closed(0, current));
+
+        let neighbours = self.neighbours(current);
+        if self.g(current) > self.rhs(current) {
+            self.g[current.x as usize][current.y as usize] = self.rhs(current);
+        } else {
+            self.g[current.x as usize][current.y as usize] = INFINITE;
+            self.update_vertex(current);
+        }
+        for neighbour in neighbours {
+            self.update_vertex(neighbour);
+        }
+
+        if self.is_complete() {
+            self.finish();
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.g(self.start) >= INFINITE {
+            self.events.push(AlgoEvent::NoPath);
+            self.status = NoPath;
+            return;
+        }
+        let mut path = vec![self.start];
+        let mut current = self.start;
+        while !self.ends.contains(&current) && path.len() < (self.width * self.height) as usize {
+            let next = self.neighbours(current).into_iter()
+                .filter(|&next| self.g(next) < INFINITE && self.edge_cost(current, next) < INFINITE)
+                .min_by_key(|&next| self.edge_cost(current, next) + self.g(next));
+            match next {
+                Some(next) => {
+                    path.push(next);
+                    current = next;
+                }
+                None => {
+                    self.events.push(AlgoEvent::NoPath);
+                    self.status = NoPath;
+                    return;
+                }
+            }
+        }
+        let path = Path::Tiles(path);
+        self.events.push(AlgoEvent::Finished(path.clone()));
+        self.status = Found(path, vec![]);
+    }
+}
+
+impl Algorithm for DStarLite {
+    fn tick(&mut self) {
+        match self.status {
+            AlgoStatus::InProgress(_) => self.process_once(),
+            _ => {
+                //do nothing
+            }
+        }
+    }
+
+    fn get_data(&self) -> &AlgoStatus {
+        return &self.status;
+    }
+
+    fn supports_cost_changes(&self) -> bool {
+        true
+    }
+
+    fn cost_changed(&mut self, xy: Coord) {
+        //Changing a tile also changes whether diagonal moves around it are allowed
+        //so every neighbour has to be checked, not just the tiles that can reach it
+        if !matches!(self.status, InProgress(_)) {
+            self.events.push(AlgoEvent::Resumed);
+        }
+        for x in -1..=1 {
+            for y in -1..=1 {
+                self.update_vertex(xy + (x, y));
+            }
+        }
+        self.status = InProgress(vec![]);
+    }
+
+    fn take_events(&mut self) -> Vec<AlgoEvent> {
+        return std::mem::take(&mut self.events);
+    }
+}
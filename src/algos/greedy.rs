@@ -0,0 +1,92 @@
+use std::rc::Rc;
+use crate::data::Coord;
+use crate::data::cost::Cost;
+use crate::data::diagonal::Diagonal;
+use crate::data::heuristic::{Heuristic, Combination};
+use crate::algos::node_arena::{NodeArena, Membership};
+use crate::algos::{Algorithm, AlgoStatus, AlgoEvent, Frontier, Path, CostCalc};
+use crate::algos::AlgoStatus::*;
+
+//Only the heuristic is used to order the open nodes, the distance travelled is ignored
+pub struct Greedy {
+    diagonal: Diagonal,
+    width: i32,
+    height: i32,
+    nodes: NodeArena,
+    cost_calc: Rc<Box<CostCalc>>,
+    start: Coord,
+    ends: Vec<Coord>,
+    heuristic: Heuristic,
+    combination: Combination,
+    status: AlgoStatus,
+}
+
+impl Greedy {
+    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal, heuristic: Heuristic, combination: Combination) -> Greedy {
+        let mut nodes = NodeArena::new(width, height);
+        nodes.open(start, None, Cost::ZERO, Cost::ZERO, Cost::ZERO);
+        Greedy {
+            width,
+            height,
+            diagonal,
+            nodes,
+            cost_calc: Rc::new(cost_calc),
+            start,
+            ends,
+            heuristic,
+            combination,
+            status: AlgoStatus::InProgress(vec![]),
+        }
+    }
+}
+
+impl Greedy {
+    fn process_once(&mut self) {
+        let current = match self.nodes.pop() {
+            Some(xy) => xy,
+            None => {
+                self.nodes.record(AlgoEvent::NoPath);
+                self.status = NoPath;
+                return;
+            }
+        };
+
+        if self.ends.contains(&current) {
+            let path = Path::Tiles(self.nodes.path_to(current));
+            self.nodes.record(AlgoEvent::Finished(path.clone()));
+            self.status = Found(path, vec![Frontier::new(vec![], self.nodes.closed_coords())]);
+            return;
+        }
+
+        for new_pos in self.diagonal.get_neighbours(self.cost_calc.clone(), current) {
+            if new_pos.is_out_of_bounds(self.width, self.height) { continue; }
+
+            if (self.cost_calc)(new_pos) < 0 { continue; }
+
+            if self.nodes.get(new_pos).membership != Membership::Unvisited { continue; }
+
+            //g is left at 0 so f is just the heuristic
+            let h = self.heuristic.calc_multiple(&self.start, &new_pos, &self.ends, self.combination);
+            self.nodes.open(new_pos, Some(current), Cost::ZERO, h, h);
+        }
+    }
+}
+
+impl Algorithm for Greedy {
+    fn tick(&mut self) {
+        match self.status {
+            AlgoStatus::InProgress(_) => self.process_once(),
+            _ => {
+                //do nothing
+            }
+        }
+    }
+
+    fn get_data(&self) -> &AlgoStatus {
+        return &self.status;
+    }
+
+    fn take_events(&mut self) -> Vec<AlgoEvent> {
+        return self.nodes.take_events();
+    }
+}
\ No newline at end of file
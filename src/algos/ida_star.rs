@@ -0,0 +1,164 @@
+use std::rc::Rc;
+use crate::data::Coord;
+use crate::data::cost::Cost;
+use crate::data::diagonal::Diagonal;
+use crate::data::cost_model::CostModel;
+use crate::data::heuristic::{Heuristic, Combination};
+use crate::algos::{Algorithm, AlgoStatus, AlgoEvent, Path, CostCalc};
+use crate::algos::AlgoStatus::*;
+
+struct Frame {
+    xy: Coord,
+    g: Cost,
+    neighbours: Vec<Coord>,
+    next: usize,
+}
+
+//Iterative deepening A*, a depth first search that gives up on any node with an f above the threshold
+//If an iteration fails the threshold is raised to the smallest f that went over it and the search starts again
+//Only the current path (the stack) is kept in memory, there is no open or closed list
+//The stack is reported as open and tiles popped off it as closed, each iteration starts with Restarted
+//Each iteration remembers the cheapest g it reached every tile with, getting to a tile again for no less has nothing new to find
+pub struct IdaStar {
+    diagonal: Diagonal,
+    cost_model: CostModel,
+    width: i32,
+    height: i32,
+    start: Coord,
+    ends: Vec<Coord>,
+    stack: Vec<Frame>,
+    //cheapest g each tile has been pushed with this iteration
+    best_g: Vec<Vec<Cost>>,
+    threshold: Cost,
+    next_threshold: Cost,
+    iteration: usize,
+    cost_calc: Rc<Box<CostCalc>>,
+    heuristic: Heuristic,
+    combination: Combination,
+    status: AlgoStatus,
+    events: Vec<AlgoEvent>,
+}
+
+impl IdaStar {
+    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal, cost_model: CostModel, heuristic: Heuristic, combination: Combination) -> IdaStar {
+        let threshold = heuristic.calc_multiple(&start, &start, &ends, combination);
+        let mut algo = IdaStar {
+            width,
+            height,
+            diagonal,
+            cost_model,
+            start,
+            ends,
+            stack: vec![],
+            best_g: vec![vec![Cost::INFINITE; height as usize]; width as usize],
+            threshold,
+            next_threshold: Cost::INFINITE,
+            iteration: 1,
+            cost_calc: Rc::new(cost_calc),
+            heuristic,
+            combination,
+            status: AlgoStatus::InProgress(vec![]),
+            events: vec![],
+        };
+        algo.push(start, Cost::ZERO, threshold);
+        return algo;
+    }
+}
+
+impl IdaStar {
+    fn push(&mut self, xy: Coord, g: Cost, h: Cost) {
+        let neighbours = self.diagonal.get_neighbours(self.cost_calc.clone(), xy).into_iter()
+            .filter(|next| !next.is_out_of_bounds(self.width, self.height))
+            .collect();
+        let parent = self.stack.last().map(|frame| frame.xy);
+        self.best_g[xy.x as usize][xy.y as usize] = g;
+        self.events.push(AlgoEvent::Opened(0, xy, parent, g, h, g + h));
+        self.stack.push(Frame { xy, g, neighbours, next: 0 });
+    }
+
+    fn finish(&mut self, path: Vec<Coord>) {
+        let path = Path::Tiles(path);
+        self.events.push(AlgoEvent::Finished(path.clone()));
+        self.status = Found(path, vec![]);
+    }
+
+    fn start_next_iteration(&mut self) {
+        if self.next_threshold == Cost::INFINITE {
+            self.events.push(AlgoEvent::NoPath);
+            self.status = NoPath;
+            return;
+        }
+        self.threshold = self.next_threshold;
+        self.next_threshold = Cost::INFINITE;
+        self.iteration += 1;
+        self.best_g = vec![vec![Cost::INFINITE; self.height as usize]; self.width as usize];
+        self.events.push(AlgoEvent::Restarted);
+        let h = self.heuristic.calc_multiple(&self.start, &self.start, &self.ends, self.combination);
+        self.push(self.start, Cost::ZERO, h);
+    }
+
+    fn process_once(&mut self) {
+        if self.ends.contains(&self.start) {
+            self.finish(vec![self.start]);
+            return;
+        }
+
+        if self.stack.is_empty() {
+            self.start_next_iteration();
+            if let NoPath = self.status {
+                return;
+            }
+        }
+
+        let top = self.stack.last_mut().unwrap();
+        if top.next >= top.neighbours.len() {
+            let xy = top.xy;
+            self.stack.pop();
+            self.events.push(AlgoEvent::Closed(0, xy));
+            return;
+        }
+        let next = top.neighbours[top.next];
+        top.next += 1;
+        let top_xy = top.xy;
+        let top_g = top.g;
+
+        //also skips tiles already on the stack, going round in a circle only adds to g
+        let g = top_g + self.cost_model.step_cost(self.cost_calc.as_ref(), top_xy, next);
+        if g < self.best_g[next.x as usize][next.y as usize] {
+            let h = self.heuristic.calc_multiple(&self.start, &next, &self.ends, self.combination);
+            let f = g + h;
+            if f > self.threshold {
+                self.next_threshold = self.next_threshold.min(f);
+            } else if self.ends.contains(&next) {
+                let mut path: Vec<Coord> = self.stack.iter().map(|frame| frame.xy).collect();
+                path.push(next);
+                self.finish(path);
+            } else {
+                self.push(next, g, h);
+            }
+        }
+    }
+}
+
+impl Algorithm for IdaStar {
+    fn tick(&mut self) {
+        match self.status {
+            AlgoStatus::InProgress(_) => self.process_once(),
+            _ => {
+                //do nothing
+            }
+        }
+    }
+
+    fn get_data(&self) -> &AlgoStatus {
+        return &self.status;
+    }
+
+    fn get_threshold(&self) -> Option<(Cost, usize)> {
+        return Some((self.threshold, self.iteration));
+    }
+
+    fn take_events(&mut self) -> Vec<AlgoEvent> {
+        return std::mem::take(&mut self.events);
+    }
+}
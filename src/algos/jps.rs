@@ -0,0 +1,342 @@
+use std::rc::Rc;
+use crate::data::Coord;
+use crate::data::cost::Cost;
+use crate::data::diagonal::Diagonal;
+use crate::data::cost_model::CostModel;
+use crate::data::heuristic::{Heuristic, Combination};
+use crate::algos::node_arena::{NodeArena, Membership};
+use crate::algos::{Algorithm, AlgoStatus, AlgoEvent, Frontier, Path, CostCalc};
+use crate::algos::AlgoStatus::*;
+use crate::data::maps::NODE_WALL;
+
+const ORTHOGONAL_STEP: f32 = 1.;
+const DIAGONAL_STEP: f32 = std::f32::consts::SQRT_2;
+//The tiles around a tile, a bit of a wall mask or neighbour set is the tile at the same index
+const NEIGHBOURS: [(i32, i32); 8] = [(-1, -1), (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0)];
+const MASKS: usize = 1 << NEIGHBOURS.len();
+
+//Jump point search, only valid on maps where every passable tile costs the same
+//Open and closed nodes are all jump points, any tile passed over while jumping is also reported as closed
+pub struct Jps {
+    diagonal: Diagonal,
+    cost_model: CostModel,
+    width: i32,
+    height: i32,
+    nodes: NodeArena,
+    scanned: Vec<bool>,
+    scanned_nodes: Vec<Coord>,
+    jump_points: Vec<Coord>,
+    //neighbours not pruned for each direction arrived from and wall mask around the tile, indexed by `direction * MASKS + mask`
+    unpruned: Vec<u8>,
+    cost_calc: Rc<Box<CostCalc>>,
+    start: Coord,
+    ends: Vec<Coord>,
+    heuristic: Heuristic,
+    combination: Combination,
+    status: AlgoStatus,
+}
+
+impl Jps {
+    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal, cost_model: CostModel, heuristic: Heuristic, combination: Combination) -> Jps {
+        let mut nodes = NodeArena::new(width, height);
+        nodes.open(start, None, Cost::ZERO, Cost::ZERO, Cost::ZERO);
+        Jps {
+            width,
+            height,
+            diagonal,
+            cost_model,
+            nodes,
+            scanned: vec![false; (width * height) as usize],
+            scanned_nodes: vec![],
+            jump_points: vec![start],
+            unpruned: unpruned_table(diagonal),
+            cost_calc: Rc::new(cost_calc),
+            start,
+            ends,
+            heuristic,
+            combination,
+            status: AlgoStatus::InProgress(vec![]),
+        }
+    }
+}
+
+fn signum(coord: Coord) -> Coord {
+    Coord::new(coord.x.signum(), coord.y.signum())
+}
+
+fn is_diagonal(dir: Coord) -> bool {
+    dir.x != 0 && dir.y != 0
+}
+
+fn step_length(from: Coord, to: Coord) -> f32 {
+    if is_diagonal(to - from) { DIAGONAL_STEP } else { ORTHOGONAL_STEP }
+}
+
+fn neighbour_index(offset: Coord) -> usize {
+    NEIGHBOURS.iter().position(|&(x, y)| offset.x == x && offset.y == y).expect("Not a neighbour")
+}
+
+//Shortest distance from `from` to `to` using only the tiles around (and excluding) `centre`
+//Every tile costs the same so only the number and kind of steps matter
+fn local_distance(diagonal: Diagonal, cost_calc: &Rc<Box<CostCalc>>, centre: Coord, from: Coord, to: Coord) -> f32 {
+    let idx = |xy: Coord| ((xy.x - centre.x + 1) * 3 + (xy.y - centre.y + 1)) as usize;
+    let mut distances = [f32::INFINITY; 9];
+    distances[idx(from)] = 0.;
+    //relaxed until nothing gets shorter, at most once per tile around the centre
+    for _ in 0..8 {
+        let mut changed = false;
+        for x in -1..=1 {
+            for y in -1..=1 {
+                let cell = centre + (x, y);
+                if cell == centre || distances[idx(cell)].is_infinite() { continue; }
+                for next in diagonal.get_neighbours(cost_calc.clone(), cell) {
+                    if next == centre || (next.x - centre.x).abs() > 1 || (next.y - centre.y).abs() > 1 { continue; }
+                    let distance = distances[idx(cell)] + step_length(cell, next);
+                    if distance < distances[idx(next)] {
+                        distances[idx(next)] = distance;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    return distances[idx(to)];
+}
+
+//Neighbours of `xy` that can only be reached optimally by going through `xy` when arriving from `dir`
+fn unpruned_neighbours(diagonal: Diagonal, cost_calc: &Rc<Box<CostCalc>>, xy: Coord, dir: Coord) -> Vec<Coord> {
+    let parent = xy - dir;
+    return diagonal.get_neighbours(cost_calc.clone(), xy).into_iter()
+        .filter(|&next| next != parent)
+        .filter(|&next| {
+            let through = step_length(parent, xy) + step_length(xy, next);
+            let around = local_distance(diagonal, cost_calc, xy, parent, next);
+            if is_diagonal(dir) { around >= through } else { around > through }
+        })
+        .collect();
+}
+
+//Which neighbours survive pruning only depends on the direction and the walls around a tile,
+//so it's worked out once for every direction and wall mask on a 3x3 grid
+fn unpruned_table(diagonal: Diagonal) -> Vec<u8> {
+    let centre = Coord::new(1, 1);
+    let mut table = vec![0; NEIGHBOURS.len() * MASKS];
+    for mask in 0..MASKS {
+        let cost_calc: Rc<Box<CostCalc>> = Rc::new(Box::new(move |xy: Coord| {
+            let offset = xy - centre;
+            if offset.x == 0 && offset.y == 0 {
+                return 0;
+            }
+            match NEIGHBOURS.iter().position(|&(x, y)| offset.x == x && offset.y == y) {
+                Some(bit) if mask & 1 << bit == 0 => 0,
+                _ => NODE_WALL,
+            }
+        }));
+        for (direction, &dir) in NEIGHBOURS.iter().enumerate() {
+            table[direction * MASKS + mask] = unpruned_neighbours(diagonal, &cost_calc, centre, Coord::new(dir.0, dir.1)).iter()
+                .fold(0, |bits, &next| bits | 1 << neighbour_index(next - centre));
+        }
+    }
+    return table;
+}
+
+impl Jps {
+    fn is_passable(&self, xy: Coord) -> bool {
+        !xy.is_out_of_bounds(self.width, self.height) && (self.cost_calc)(xy) >= 0
+    }
+
+    //Whether the step is allowed without listing every neighbour of `from`
+    fn can_step(&self, from: Coord, to: Coord) -> bool {
+        if !self.is_passable(to) {
+            return false;
+        }
+        if !is_diagonal(to - from) {
+            return true;
+        }
+        if self.diagonal == Diagonal::Never {
+            return false;
+        }
+        let walls = [from + (to.x - from.x, 0), from + (0, to.y - from.y)].iter()
+            .filter(|&&side| !self.is_passable(side))
+            .count();
+        return walls <= self.diagonal.max_walls();
+    }
+
+    fn mark_scanned(&mut self, xy: Coord) {
+        let idx = (xy.x * self.height + xy.y) as usize;
+        if !self.scanned[idx] {
+            self.scanned[idx] = true;
+            self.scanned_nodes.push(xy);
+            self.nodes.record(AlgoEvent::Passed(0, xy));
+        }
+    }
+
+    //Bit set for each tile around `xy` that can't be walked on
+    fn wall_mask(&self, xy: Coord) -> usize {
+        return NEIGHBOURS.iter().enumerate()
+            .filter(|(_, &offset)| !self.is_passable(xy + offset))
+            .fold(0, |mask, (bit, _)| mask | 1 << bit);
+    }
+
+    fn unpruned(&self, dir: Coord, mask: usize) -> u8 {
+        return self.unpruned[neighbour_index(dir) * MASKS + mask];
+    }
+
+    fn has_forced_neighbours(&self, xy: Coord, dir: Coord) -> bool {
+        if self.diagonal == Diagonal::Never {
+            if dir.x != 0 {
+                return false;
+            }
+            return [-1, 1].iter().any(|&side| self.is_passable(xy + (side, 0)) && !self.is_passable(xy + (side, -dir.y)));
+        }
+        //forced neighbours are the ones only kept because of a wall, with no walls around only the natural ones are
+        return self.unpruned(dir, self.wall_mask(xy)) & !self.unpruned(dir, 0) != 0;
+    }
+
+    fn successor_directions(&self, xy: Coord, dir: Option<Coord>) -> Vec<Coord> {
+        let dir = match dir {
+            Some(dir) => dir,
+            None => {
+                return self.diagonal.get_neighbours(self.cost_calc.clone(), xy).into_iter()
+                    .map(|next| next - xy)
+                    .collect();
+            }
+        };
+        if self.diagonal == Diagonal::Never {
+            let mut directions = vec![dir];
+            if dir.x != 0 {
+                directions.push(Coord::new(0, 1));
+                directions.push(Coord::new(0, -1));
+            } else {
+                for &side in [-1, 1].iter() {
+                    if self.is_passable(xy + (side, 0)) && !self.is_passable(xy + (side, -dir.y)) {
+                        directions.push(Coord::new(side, 0));
+                    }
+                }
+            }
+            return directions;
+        }
+        let unpruned = self.unpruned(dir, self.wall_mask(xy));
+        return NEIGHBOURS.iter().enumerate()
+            .filter(|(bit, _)| unpruned & 1 << bit != 0)
+            .map(|(_, &(x, y))| Coord::new(x, y))
+            .collect();
+    }
+
+    fn jump(&mut self, from: Coord, dir: Coord) -> Option<Coord> {
+        let mut current = from;
+        loop {
+            let next = current + dir;
+            if !self.can_step(current, next) {
+                return None;
+            }
+            self.mark_scanned(next);
+
+            if self.ends.contains(&next) || self.has_forced_neighbours(next, dir) {
+                return Some(next);
+            }
+
+            let branches = if is_diagonal(dir) {
+                vec![Coord::new(dir.x, 0), Coord::new(0, dir.y)]
+            } else if self.diagonal == Diagonal::Never && dir.x != 0 {
+                vec![Coord::new(0, 1), Coord::new(0, -1)]
+            } else {
+                vec![]
+            };
+            for branch in branches {
+                if self.jump(next, branch).is_some() {
+                    return Some(next);
+                }
+            }
+
+            current = next;
+        }
+    }
+
+    fn process_once(&mut self) {
+        let current = match self.nodes.pop() {
+            Some(xy) => xy,
+            None => {
+                self.nodes.record(AlgoEvent::NoPath);
+                self.status = NoPath;
+                return;
+            }
+        };
+
+        if self.ends.contains(&current) {
+            let jump_path = self.nodes.path_to(current);
+            let mut path = vec![jump_path[0]];
+            for pair in jump_path.windows(2) {
+                let dir = signum(pair[1] - pair[0]);
+                let mut step = pair[0];
+                while step != pair[1] {
+                    step = step + dir;
+                    path.push(step);
+                }
+            }
+            let path = Path::Tiles(path);
+            self.nodes.record(AlgoEvent::Finished(path.clone()));
+            self.status = Found(path, vec![Frontier::new(vec![], self.closed_coords())]);
+            return;
+        }
+
+        let current_g = self.nodes.get(current).g;
+        let dir = self.nodes.parent(current).map(|parent| signum(current - parent));
+        for dir in self.successor_directions(current, dir) {
+            let jump_point = match self.jump(current, dir) {
+                Some(xy) => xy,
+                None => continue
+            };
+
+            let existing = self.nodes.get(jump_point);
+            if existing.membership == Membership::Closed { continue; }
+
+            let distance = jump_point - current;
+            let steps = distance.x.abs().max(distance.y.abs());
+            let g = current_g + self.cost_model.step_cost(self.cost_calc.as_ref(), current, current + dir) * steps;
+            if existing.g <= g { continue; }
+
+            if !self.jump_points.contains(&jump_point) {
+                self.jump_points.push(jump_point);
+            }
+            let h = self.heuristic.calc_multiple(&self.start, &jump_point, &self.ends, self.combination);
+            self.nodes.open(jump_point, Some(current), g, h, g + h);
+        }
+    }
+
+    fn closed_coords(&self) -> Vec<Coord> {
+        let mut closed: Vec<Coord> = self.scanned_nodes.clone();
+        for xy in self.nodes.closed_coords() {
+            if !self.scanned[(xy.x * self.height + xy.y) as usize] {
+                closed.push(xy);
+            }
+        }
+        return closed;
+    }
+}
+
+impl Algorithm for Jps {
+    fn tick(&mut self) {
+        match self.status {
+            AlgoStatus::InProgress(_) => self.process_once(),
+            _ => {
+                //do nothing
+            }
+        }
+    }
+
+    fn get_data(&self) -> &AlgoStatus {
+        return &self.status;
+    }
+
+    fn get_jump_points(&self) -> Option<&Vec<Coord>> {
+        return Some(&self.jump_points);
+    }
+
+    fn take_events(&mut self) -> Vec<AlgoEvent> {
+        return self.nodes.take_events();
+    }
+}
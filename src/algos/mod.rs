@@ -1,43 +1,231 @@
 pub mod astar;
+pub mod bfs;
+pub mod bidirectional;
 pub mod dijkstra;
+pub mod dstar_lite;
+pub mod greedy;
+pub mod ida_star;
+pub mod jps;
+pub mod node_arena;
+pub mod search_tracker;
+pub mod theta;
+pub mod timeline;
+pub mod tour;
+
+#[cfg(test)]
+mod tests;
 
 use crate::data::Coord;
+use crate::data::diagonal::Diagonal;
+use crate::data::cost_model::CostModel;
+use crate::data::cost::Cost;
+use std::rc::Rc;
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
 
 pub type CostCalc = dyn Fn(Coord) -> i32;
 
+//How much longer a diagonal step is than a cardinal one, √2 is the true distance between tile centres
+pub const DIAGONAL_FACTOR: f32 = std::f32::consts::SQRT_2;
+
+//Cost of a diagonal step across a free tile
+//Rounded up so heuristics using the true distance never overestimate
+pub fn diagonal_step_cost(diagonal_factor: f32) -> Cost {
+    return Cost::ceil(diagonal_factor as f64);
+}
+
+pub fn path_cost(cost_model: CostModel, cost_calc: &CostCalc, path: &[Coord]) -> Cost {
+    return path.windows(2)
+        .map(|pair| cost_model.step_cost(cost_calc, pair[0], pair[1]))
+        .sum();
+}
+
+//Cheapest possible cost from start to any of the ends, used to compare against the path an algorithm found
+pub fn optimal_cost(start: Coord, ends: &[Coord], cost_calc: Rc<Box<CostCalc>>, width: i32, height: i32, diagonal: Diagonal, cost_model: CostModel) -> Option<Cost> {
+    let mut costs = vec![vec![Cost::INFINITE; height as usize]; width as usize];
+    let mut queue = BinaryHeap::new();
+    costs[start.x as usize][start.y as usize] = Cost::ZERO;
+    queue.push(Reverse((Cost::ZERO, start.x, start.y)));
+    while let Some(Reverse((cost, x, y))) = queue.pop() {
+        let current = Coord::new(x, y);
+        if ends.contains(&current) {
+            return Some(cost);
+        }
+        if cost > costs[x as usize][y as usize] {
+            continue;
+        }
+        for next in diagonal.get_neighbours(cost_calc.clone(), current) {
+            if next.is_out_of_bounds(width, height) { continue; }
+            let next_cost = cost + cost_model.step_cost(cost_calc.as_ref(), current, next);
+            if next_cost < costs[next.x as usize][next.y as usize] {
+                costs[next.x as usize][next.y as usize] = next_cost;
+                queue.push(Reverse((next_cost, next.x, next.y)));
+            }
+        }
+    }
+    return None;
+}
+
+#[derive(Clone)]
 pub enum AlgoStatus {
-    InProgress((Vec<Coord>, Vec<Coord>)),
-    Found(Vec<Coord>, Vec<Coord>),
+    InProgress(Vec<Frontier>),
+    Found(Path, Vec<Frontier>),
+    NoPath,
+}
+
+//Most algorithms have one frontier, ones that search from several places at once have one per search
+#[derive(Clone)]
+pub struct Frontier {
+    pub open: Vec<Coord>,
+    pub closed: Vec<Coord>,
+}
+
+impl Frontier {
+    pub fn new(open: Vec<Coord>, closed: Vec<Coord>) -> Frontier {
+        Frontier { open, closed }
+    }
+}
+
+//Changes made by a tick, the search at any tick is rebuilt from them rather than each algorithm keeping a snapshot
+//Every tile event starts with the index of the frontier it belongs to, 0 unless the algorithm searches from several places
+#[derive(Clone)]
+pub enum AlgoEvent {
+    //(frontier, tile, parent, g, h, f), also sent when a closed tile is opened again
+    //f is the one the open list is ordered by, which may not be g + h, such as with a weight
+    Opened(usize, Coord, Option<Coord>, Cost, Cost, Cost),
+    Closed(usize, Coord),
+    //(frontier, tile, new parent, new g, new f) when an open tile is reached by a cheaper route
+    ParentChanged(usize, Coord, Coord, Cost, Cost),
+    //(frontier, tile) when an open tile stops being open without being closed, it's forgotten
+    Dropped(usize, Coord),
+    //A tile looked at but not opened, such as one JPS jumped over
+    Passed(usize, Coord),
+    //Everything before this is forgotten, the search started over
+    Restarted,
+    //A finished search carrying on, such as D* Lite after a tile cost changed
+    Resumed,
+    Finished(Path),
     NoPath,
 }
 
+#[derive(Clone)]
+pub enum Path {
+    //Every tile on the path, each one next to the previous
+    Tiles(Vec<Coord>),
+    //Points joined by straight lines, tiles between them are skipped
+    Waypoints(Vec<Coord>),
+}
+
+impl Path {
+    pub fn coords(&self) -> &Vec<Coord> {
+        return match self {
+            Path::Tiles(coords) => coords,
+            Path::Waypoints(coords) => coords,
+        };
+    }
+
+    pub fn length(&self) -> f32 {
+        return self.coords().windows(2)
+            .map(|pair| {
+                let diff = pair[1] - pair[0];
+                ((diff.x * diff.x + diff.y * diff.y) as f32).sqrt()
+            })
+            .sum();
+    }
+}
+
 pub trait Algorithm {
     fn tick(&mut self);
     fn get_data(&self) -> &AlgoStatus;
+
+    fn get_jump_points(&self) -> Option<&Vec<Coord>> {
+        None
+    }
+
+    fn supports_cost_changes(&self) -> bool {
+        false
+    }
+
+    //Called after the cost of a tile has been changed, only if supports_cost_changes() is true
+    fn cost_changed(&mut self, _xy: Coord) {}
+
+    //Only algorithms that multiply the heuristic by a weight return Some
+    fn get_weight(&self) -> Option<f32> {
+        None
+    }
+
+    //Changes the weight and restarts the search
+    fn set_weight(&mut self, _weight: f32) {}
+
+    //Found path cost divided by the cheapest possible path cost
+    fn get_cost_ratio(&self) -> Option<f32> {
+        None
+    }
+
+    //(f threshold, iteration) for iterative deepening algorithms
+    fn get_threshold(&self) -> Option<(Cost, usize)> {
+        None
+    }
+
+    //Paths already found by algorithms that visit every end one leg at a time
+    fn get_legs(&self) -> Option<&Vec<Path>> {
+        None
+    }
+
+    //Events since the last call
+    //The frontiers of the InProgress status are left empty, the search is shown from the events
+    fn take_events(&mut self) -> Vec<AlgoEvent>;
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum Algo {
     AStar,
-    Dijkstra
+    Dijkstra,
+    Bfs,
+    Greedy,
+    Jps,
+    Theta,
+    Bidirectional,
+    DStarLite,
+    WeightedAStar,
+    IdaStar,
+    Tour,
 }
 
 impl Algo {
     pub fn name(&self) -> String {
         return match self {
             Algo::AStar => String::from("A*"),
-            Algo::Dijkstra => String::from("Dijkstra")
+            Algo::Dijkstra => String::from("Dijkstra"),
+            Algo::Bfs => String::from("Breadth-first"),
+            Algo::Greedy => String::from("Greedy best-first"),
+            Algo::Jps => String::from("Jump point search"),
+            Algo::Theta => String::from("Theta* (any angle)"),
+            Algo::Bidirectional => String::from("Bidirectional"),
+            Algo::DStarLite => String::from("D* Lite (editable walls)"),
+            Algo::WeightedAStar => String::from("Weighted A*"),
+            Algo::IdaStar => String::from("IDA*"),
+            Algo::Tour => String::from("Visit all ends (tour)"),
         };
     }
 
     pub fn len() -> usize {
-        2
+        11
     }
 
     pub fn from_index(idx: usize) -> Algo {
         return match idx {
             0 => Algo::AStar,
             1 => Algo::Dijkstra,
+            2 => Algo::Bfs,
+            3 => Algo::Greedy,
+            4 => Algo::Jps,
+            5 => Algo::Theta,
+            6 => Algo::Bidirectional,
+            7 => Algo::DStarLite,
+            8 => Algo::WeightedAStar,
+            9 => Algo::IdaStar,
+            10 => Algo::Tour,
             _ => panic!("Invalid index: {}", idx),
         };
     }
@@ -45,7 +233,37 @@ impl Algo {
     pub fn supported_heuristics(&self) -> bool {
         match self {
             Algo::AStar => true,
-            Algo::Dijkstra => false
+            Algo::Dijkstra => false,
+            Algo::Bfs => false,
+            Algo::Greedy => true,
+            Algo::Jps => true,
+            Algo::Theta => true,
+            Algo::Bidirectional => true,
+            Algo::DStarLite => true,
+            Algo::WeightedAStar => true,
+            Algo::IdaStar => true,
+            Algo::Tour => false,
+        }
+    }
+
+    //Algorithms that never add up tile costs skip choosing a cost model
+    pub fn uses_cost_model(&self) -> bool {
+        match self {
+            Algo::AStar => true,
+            Algo::Dijkstra => true,
+            Algo::Bfs => false,
+            Algo::Greedy => false,
+            Algo::Jps => true,
+            Algo::Theta => true,
+            Algo::Bidirectional => true,
+            Algo::DStarLite => true,
+            Algo::WeightedAStar => true,
+            Algo::IdaStar => true,
+            Algo::Tour => true,
         }
     }
+
+    pub fn requires_uniform_cost(&self) -> bool {
+        matches!(self, Algo::Jps)
+    }
 }
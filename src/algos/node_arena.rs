@@ -0,0 +1,199 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use crate::data::Coord;
+use crate::data::cost::Cost;
+use crate::algos::AlgoEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Membership {
+    Unvisited,
+    Open,
+    Closed,
+}
+
+//Search state of one tile
+#[derive(Debug, Clone, Copy)]
+pub struct SearchCell {
+    //distance to start node
+    pub g: Cost,
+    //heuristic — estimated distance to end node
+    pub h: Cost,
+    //node cost
+    pub f: Cost,
+    //index of the cell this one was reached from
+    pub parent: Option<usize>,
+    pub membership: Membership,
+    //order of the heap entry that is current for this cell
+    open_order: usize,
+    closed_before: bool,
+}
+
+impl Default for SearchCell {
+    fn default() -> SearchCell {
+        SearchCell {
+            g: Cost::INFINITE,
+            h: Cost::ZERO,
+            f: Cost::INFINITE,
+            parent: None,
+            membership: Membership::Unvisited,
+            open_order: 0,
+            closed_before: false,
+        }
+    }
+}
+
+struct OpenEntry {
+    f: Cost,
+    order: usize,
+    idx: usize,
+}
+
+impl PartialEq for OpenEntry {
+    fn eq(&self, other: &OpenEntry) -> bool {
+        self.f == other.f && self.order == other.order
+    }
+}
+
+impl Eq for OpenEntry {}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &OpenEntry) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+//Reversed so the BinaryHeap (a max heap) returns the lowest f first
+//Ties go to whichever node was added first
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &OpenEntry) -> Ordering {
+        other.f.cmp(&self.f)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+//Search state for every tile of the map in one flat list, indexed by coord
+//Opening a tile that is already open replaces it, the old heap entry is left in the heap
+//and skipped when popped rather than searched for and removed
+pub struct NodeArena {
+    //which frontier the events are for, searches with more than one have an arena each
+    frontier: usize,
+    height: i32,
+    cells: Vec<SearchCell>,
+    heap: BinaryHeap<OpenEntry>,
+    next_order: usize,
+    //tiles in the order they were first closed
+    closed: Vec<Coord>,
+    events: Vec<AlgoEvent>,
+}
+
+impl NodeArena {
+    pub fn new(width: i32, height: i32) -> NodeArena {
+        NodeArena::for_frontier(width, height, 0)
+    }
+
+    pub fn for_frontier(width: i32, height: i32, frontier: usize) -> NodeArena {
+        NodeArena {
+            frontier,
+            height,
+            cells: vec![SearchCell::default(); (width * height) as usize],
+            heap: BinaryHeap::new(),
+            next_order: 0,
+            closed: vec![],
+            events: vec![],
+        }
+    }
+
+    fn index(&self, xy: Coord) -> usize {
+        (xy.x * self.height + xy.y) as usize
+    }
+
+    fn coord(&self, idx: usize) -> Coord {
+        Coord::new(idx as i32 / self.height, idx as i32 % self.height)
+    }
+
+    pub fn get(&self, xy: Coord) -> &SearchCell {
+        &self.cells[self.index(xy)]
+    }
+
+    //Opens `xy`, or reopens it if it was closed, reached from `parent`
+    pub fn open(&mut self, xy: Coord, parent: Option<Coord>, g: Cost, h: Cost, f: Cost) {
+        let idx = self.index(xy);
+        match (self.cells[idx].membership, parent) {
+            (Membership::Open, Some(parent)) => self.events.push(AlgoEvent::ParentChanged(self.frontier, xy, parent, g, f)),
+            _ => self.events.push(AlgoEvent::Opened(self.frontier, xy, parent, g, h, f)),
+        }
+        let parent = parent.map(|parent| self.index(parent));
+        let order = self.next_order;
+        self.next_order += 1;
+        let cell = &mut self.cells[idx];
+        cell.g = g;
+        cell.h = h;
+        cell.f = f;
+        cell.parent = parent;
+        cell.membership = Membership::Open;
+        cell.open_order = order;
+        self.heap.push(OpenEntry { f, order, idx });
+    }
+
+    //Closes and returns the open tile with the lowest f
+    pub fn pop(&mut self) -> Option<Coord> {
+        while let Some(entry) = self.heap.pop() {
+            let xy = self.coord(entry.idx);
+            let cell = &mut self.cells[entry.idx];
+            if cell.membership == Membership::Open && cell.open_order == entry.order {
+                cell.membership = Membership::Closed;
+                if !cell.closed_before {
+                    cell.closed_before = true;
+                    self.closed.push(xy);
+                }
+                self.events.push(AlgoEvent::Closed(self.frontier, xy));
+                return Some(xy);
+            }
+        }
+        return None;
+    }
+
+    //The f of the tile pop would return next, without closing it
+    pub fn lowest_f(&mut self) -> Option<Cost> {
+        while let Some(entry) = self.heap.peek() {
+            let cell = &self.cells[entry.idx];
+            if cell.membership == Membership::Open && cell.open_order == entry.order {
+                return Some(entry.f);
+            }
+            self.heap.pop();
+        }
+        return None;
+    }
+
+    pub fn parent(&self, xy: Coord) -> Option<Coord> {
+        self.get(xy).parent.map(|idx| self.coord(idx))
+    }
+
+    //Follows the parents back from `xy`, the path starts at the first tile opened
+    pub fn path_to(&self, xy: Coord) -> Vec<Coord> {
+        let mut path = vec![xy];
+        let mut current = self.get(xy).parent;
+        while let Some(idx) = current {
+            path.push(self.coord(idx));
+            current = self.cells[idx].parent;
+        }
+        path.reverse();
+        return path;
+    }
+
+    //For events that aren't about a single tile, such as the search finishing
+    pub fn record(&mut self, event: AlgoEvent) {
+        self.events.push(event);
+    }
+
+    pub fn take_events(&mut self) -> Vec<AlgoEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn closed_coords(&self) -> Vec<Coord> {
+        self.closed.iter()
+            .filter(|&&xy| self.get(xy).membership == Membership::Closed)
+            .copied()
+            .collect()
+    }
+}
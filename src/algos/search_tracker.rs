@@ -0,0 +1,130 @@
+use std::collections::{HashMap, HashSet};
+use crate::data::Coord;
+use crate::data::cost::Cost;
+use crate::algos::{AlgoEvent, AlgoStatus, Frontier, Path};
+use crate::algos::node_arena::Membership;
+
+#[derive(Debug, Clone, Copy)]
+pub struct TrackedTile {
+    pub membership: Membership,
+    pub parent: Option<Coord>,
+    pub g: Cost,
+    pub h: Cost,
+    //as the algorithm worked it out when the tile was opened or last re-parented
+    pub f: Cost,
+    pub opened_tick: usize,
+    pub closed_tick: Option<usize>,
+}
+
+//The tiles of one frontier
+#[derive(Default, Clone)]
+struct TrackedFrontier {
+    tiles: HashMap<Coord, TrackedTile>,
+    //tiles looked at without being opened, shown as closed
+    passed: HashSet<Coord>,
+}
+
+//Rebuilds the state of a search from an algorithm's events
+#[derive(Default, Clone)]
+pub struct SearchTracker {
+    //tick the events being applied happened on
+    tick: usize,
+    //indexed by the frontier the events name, added as they're first named
+    frontiers: Vec<TrackedFrontier>,
+    last_closed: Option<(usize, Coord)>,
+    //None while the search is running, then whether a path was found
+    outcome: Option<Option<Path>>,
+}
+
+impl SearchTracker {
+    pub fn set_tick(&mut self, tick: usize) {
+        self.tick = tick;
+    }
+
+    fn frontier_mut(&mut self, frontier: usize) -> &mut TrackedFrontier {
+        if self.frontiers.len() <= frontier {
+            self.frontiers.resize_with(frontier + 1, TrackedFrontier::default);
+        }
+        return &mut self.frontiers[frontier];
+    }
+
+    pub fn apply(&mut self, event: &AlgoEvent) {
+        match event {
+            AlgoEvent::Opened(frontier, xy, parent, g, h, f) => {
+                let tile = TrackedTile { membership: Membership::Open, parent: *parent, g: *g, h: *h, f: *f, opened_tick: self.tick, closed_tick: None };
+                self.frontier_mut(*frontier).tiles.insert(*xy, tile);
+            }
+            AlgoEvent::Closed(frontier, xy) => {
+                let tick = self.tick;
+                if let Some(tile) = self.frontier_mut(*frontier).tiles.get_mut(xy) {
+                    tile.membership = Membership::Closed;
+                    tile.closed_tick = Some(tick);
+                }
+                self.last_closed = Some((*frontier, *xy));
+            }
+            AlgoEvent::ParentChanged(frontier, xy, parent, g, f) => {
+                if let Some(tile) = self.frontier_mut(*frontier).tiles.get_mut(xy) {
+                    tile.parent = Some(*parent);
+                    tile.g = *g;
+                    tile.f = *f;
+                }
+            }
+            AlgoEvent::Dropped(frontier, xy) => {
+                self.frontier_mut(*frontier).tiles.remove(xy);
+                if self.last_closed == Some((*frontier, *xy)) {
+                    self.last_closed = None;
+                }
+            }
+            AlgoEvent::Passed(frontier, xy) => {
+                self.frontier_mut(*frontier).passed.insert(*xy);
+            }
+            AlgoEvent::Restarted => *self = SearchTracker { tick: self.tick, ..SearchTracker::default() },
+            AlgoEvent::Resumed => self.outcome = None,
+            AlgoEvent::Finished(path) => self.outcome = Some(Some(path.clone())),
+            AlgoEvent::NoPath => self.outcome = Some(None),
+        }
+    }
+
+    //What each frontier knows about `xy`, with the frontier's index
+    pub fn tiles(&self, xy: Coord) -> Vec<(usize, &TrackedTile)> {
+        self.frontiers.iter()
+            .enumerate()
+            .filter_map(|(idx, frontier)| frontier.tiles.get(&xy).map(|tile| (idx, tile)))
+            .collect()
+    }
+
+    pub fn was_passed(&self, xy: Coord) -> bool {
+        self.frontiers.iter().any(|frontier| frontier.passed.contains(&xy))
+    }
+
+    pub fn frontier_count(&self) -> usize {
+        self.frontiers.len()
+    }
+
+    //The tile most recently closed and what was known about it
+    pub fn last_closed(&self) -> Option<(Coord, TrackedTile)> {
+        let (frontier, xy) = self.last_closed?;
+        return self.frontiers[frontier].tiles.get(&xy).map(|tile| (xy, *tile));
+    }
+
+    pub fn status(&self) -> AlgoStatus {
+        let frontiers = self.frontiers.iter().map(|frontier| frontier.status()).collect();
+        return match &self.outcome {
+            None => AlgoStatus::InProgress(frontiers),
+            Some(Some(path)) => AlgoStatus::Found(path.clone(), frontiers),
+            Some(None) => AlgoStatus::NoPath,
+        };
+    }
+}
+
+impl TrackedFrontier {
+    fn status(&self) -> Frontier {
+        let with = |membership: Membership| self.tiles.iter()
+            .filter(|(_, tile)| tile.membership == membership)
+            .map(|(&xy, _)| xy)
+            .collect();
+        let mut closed: Vec<Coord> = with(Membership::Closed);
+        closed.extend(self.passed.iter().filter(|xy| !self.tiles.contains_key(xy)));
+        return Frontier::new(with(Membership::Open), closed);
+    }
+}
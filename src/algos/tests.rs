@@ -0,0 +1,830 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::fs;
+use crate::data::Coord;
+use crate::data::diagonal::Diagonal;
+use crate::data::heuristic::{Heuristic, Combination};
+use crate::data::maps::{Map, NODE_WALL, parse_map, map_file_name};
+use crate::data::cost_model::CostModel;
+use crate::data::cost::Cost;
+use crate::algos::{Algorithm, AlgoStatus, AlgoEvent, Frontier, CostCalc, path_cost};
+use crate::algos::astar::Astar;
+use crate::algos::dijkstra::Dijkstra;
+use crate::algos::bfs::Bfs;
+use crate::algos::greedy::Greedy;
+use crate::algos::theta::{Theta, line_cost};
+use crate::algos::bidirectional::Bidirectional;
+use crate::algos::ida_star::IdaStar;
+use crate::algos::dstar_lite::DStarLite;
+use crate::algos::jps::Jps;
+use crate::algos::tour::Tour;
+use crate::algos::Algo;
+use crate::project_resources_dir;
+use crate::algos::search_tracker::SearchTracker;
+use crate::algos::timeline::Timeline;
+
+const WIDTH: i32 = 7;
+const HEIGHT: i32 = 5;
+const INFINITE: Cost = Cost::INFINITE;
+//IDA* revisits nodes every iteration so it can take far more ticks than there are tiles
+const MAX_TICKS: usize = 100_000;
+
+//Small deterministic grids with a mix of walls and tile costs
+fn make_grid(seed: u64) -> Vec<Vec<i32>> {
+    let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    let mut grid = vec![vec![0; HEIGHT as usize]; WIDTH as usize];
+    for column in grid.iter_mut() {
+        for cost in column.iter_mut() {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let roll = (state >> 33) % 10;
+            *cost = match roll {
+                0 | 1 => NODE_WALL,
+                2..=5 => 0,
+                _ => ((state >> 40) % 8) as i32 + 1,
+            };
+        }
+    }
+    return grid;
+}
+
+//A grid written the way it's drawn, one string per row with 9 for walls
+fn grid_from_rows(rows: [&str; HEIGHT as usize]) -> Vec<Vec<i32>> {
+    let mut grid = vec![vec![0; HEIGHT as usize]; WIDTH as usize];
+    for (y, row) in rows.iter().enumerate() {
+        for (x, letter) in row.chars().enumerate() {
+            grid[x][y] = match letter.to_digit(10).unwrap() as i32 {
+                9 => NODE_WALL,
+                cost => cost,
+            };
+        }
+    }
+    return grid;
+}
+
+fn make_cost_calc(grid: &[Vec<i32>]) -> Box<CostCalc> {
+    let grid = grid.to_vec();
+    return Box::new(move |xy: Coord| {
+        if xy.is_out_of_bounds(WIDTH, HEIGHT) {
+            return -1;
+        }
+        return grid[xy.x as usize][xy.y as usize];
+    });
+}
+
+//Reads the grid each time so edits made during a search are seen by it
+fn make_shared_cost_calc(grid: &Rc<RefCell<Vec<Vec<i32>>>>) -> Box<CostCalc> {
+    let grid = grid.clone();
+    return Box::new(move |xy: Coord| {
+        if xy.is_out_of_bounds(WIDTH, HEIGHT) {
+            return -1;
+        }
+        return grid.borrow()[xy.x as usize][xy.y as usize];
+    });
+}
+
+fn index(xy: Coord) -> usize {
+    (xy.x * HEIGHT + xy.y) as usize
+}
+
+fn coords() -> Vec<Coord> {
+    let mut coords = vec![];
+    for x in 0..WIDTH {
+        for y in 0..HEIGHT {
+            coords.push(Coord::new(x, y));
+        }
+    }
+    return coords;
+}
+
+//Floyd-Warshall over every tile, the reference the algorithms are checked against
+fn all_pairs(grid: &[Vec<i32>], diagonal: Diagonal, cost_model: CostModel) -> Vec<Vec<Cost>> {
+    return all_pairs_on(Rc::new(make_cost_calc(grid)), WIDTH, HEIGHT, diagonal, cost_model);
+}
+
+//Indexed the same way as `index`, by x * height + y
+fn all_pairs_on(cost_calc: Rc<Box<CostCalc>>, width: i32, height: i32, diagonal: Diagonal, cost_model: CostModel) -> Vec<Vec<Cost>> {
+    let count = (width * height) as usize;
+    let index = |xy: Coord| (xy.x * height + xy.y) as usize;
+    let mut dist = vec![vec![INFINITE; count]; count];
+    for xy in (0..width).flat_map(|x| (0..height).map(move |y| Coord::new(x, y))) {
+        dist[index(xy)][index(xy)] = Cost::ZERO;
+        if cost_calc(xy) == NODE_WALL {
+            continue;
+        }
+        for next in diagonal.get_neighbours(cost_calc.clone(), xy) {
+            if next.is_out_of_bounds(width, height) { continue; }
+            dist[index(xy)][index(next)] = cost_model.step_cost(cost_calc.as_ref(), xy, next);
+        }
+    }
+    for k in 0..count {
+        for i in 0..count {
+            for j in 0..count {
+                if dist[i][k] + dist[k][j] < dist[i][j] {
+                    dist[i][j] = dist[i][k] + dist[k][j];
+                }
+            }
+        }
+    }
+    return dist;
+}
+
+fn run(algo: &mut dyn Algorithm) -> Option<Vec<Coord>> {
+    for _ in 0..MAX_TICKS {
+        algo.tick();
+        match algo.get_data() {
+            AlgoStatus::InProgress(_) => {}
+            AlgoStatus::Found(path, _) => return Some(path.coords().clone()),
+            AlgoStatus::NoPath => return None,
+        }
+    }
+    panic!("algorithm did not finish");
+}
+
+fn check_path(grid: &[Vec<i32>], diagonal: Diagonal, start: Coord, ends: &[Coord], path: &[Coord]) {
+    let cost_calc = Rc::new(make_cost_calc(grid));
+    assert_eq!(path.first(), Some(&start));
+    assert!(ends.contains(path.last().unwrap()));
+    for pair in path.windows(2) {
+        assert!(diagonal.get_neighbours(cost_calc.clone(), pair[0]).contains(&pair[1]), "{} is not next to {}", pair[1], pair[0]);
+    }
+}
+
+//Only settings that `include` returns true for are checked
+fn check_optimal(name: &str, include: impl Fn(Diagonal, CostModel) -> bool, end_count: usize, make_algo: impl FnMut(Coord, Vec<Coord>, Box<CostCalc>, Diagonal, CostModel) -> Box<dyn Algorithm>) {
+    check_optimal_on(name, (0..20).map(make_grid).collect(), include, end_count, make_algo);
+}
+
+fn check_optimal_on(name: &str, grids: Vec<Vec<Vec<i32>>>, include: impl Fn(Diagonal, CostModel) -> bool, end_count: usize, mut make_algo: impl FnMut(Coord, Vec<Coord>, Box<CostCalc>, Diagonal, CostModel) -> Box<dyn Algorithm>) {
+    for (seed, grid) in grids.into_iter().enumerate() {
+        let cost_calc = make_cost_calc(&grid);
+        let free: Vec<Coord> = coords().into_iter().filter(|&xy| cost_calc(xy) != NODE_WALL).collect();
+        for diagonal in (0..Diagonal::len()).map(Diagonal::from_index) {
+            for cost_model in (0..CostModel::len()).map(CostModel::from_index) {
+                if !include(diagonal, cost_model) { continue; }
+                let dist = all_pairs(&grid, diagonal, cost_model);
+                for (i, &start) in free.iter().enumerate().step_by(3) {
+                    let ends: Vec<Coord> = [i * 7 + 5, i * 11 + 2].iter().take(end_count).map(|j| free[j % free.len()]).collect();
+                    let expected = ends.iter().map(|&end| dist[index(start)][index(end)]).min().unwrap();
+                    let mut algo = make_algo(start, ends.clone(), make_cost_calc(&grid), diagonal, cost_model);
+                    match run(algo.as_mut()) {
+                        Some(path) => {
+                            check_path(&grid, diagonal, start, &ends, &path);
+                            assert_eq!(path_cost(cost_model, cost_calc.as_ref(), &path), expected,
+                                       "{} found a more expensive path from {} to {:?} on grid {} with {:?} and {:?}", name, start, ends, seed, diagonal, cost_model);
+                        }
+                        None => assert_eq!(expected, INFINITE, "{} found no path from {} to {:?} on grid {} with {:?}", name, start, ends, seed, diagonal),
+                    }
+                }
+            }
+        }
+    }
+}
+
+//For algorithms that don't promise the cheapest path, only that they find one whenever there is one
+//`check` is also given the cost of the cheapest path
+fn check_reachable(name: &str, mut make_algo: impl FnMut(Coord, Vec<Coord>, Box<CostCalc>, Diagonal, CostModel) -> Box<dyn Algorithm>, mut check: impl FnMut(&[Vec<i32>], Diagonal, CostModel, Coord, &[Coord], &[Coord], Cost)) {
+    for seed in 0..20 {
+        let grid = make_grid(seed);
+        let cost_calc = make_cost_calc(&grid);
+        let free: Vec<Coord> = coords().into_iter().filter(|&xy| cost_calc(xy) != NODE_WALL).collect();
+        for diagonal in (0..Diagonal::len()).map(Diagonal::from_index) {
+            for cost_model in (0..CostModel::len()).map(CostModel::from_index) {
+                let dist = all_pairs(&grid, diagonal, cost_model);
+                for (i, &start) in free.iter().enumerate().step_by(3) {
+                    let ends = vec![free[(i * 7 + 5) % free.len()]];
+                    let cheapest = dist[index(start)][index(ends[0])];
+                    let mut algo = make_algo(start, ends.clone(), make_cost_calc(&grid), diagonal, cost_model);
+                    match run(algo.as_mut()) {
+                        Some(path) => {
+                            assert!(cheapest < INFINITE, "{} found a path from {} to {:?} on grid {} with {:?} where there is none", name, start, ends, seed, diagonal);
+                            check(&grid, diagonal, cost_model, start, &ends, &path, cheapest);
+                        }
+                        None => assert_eq!(cheapest, INFINITE, "{} found no path from {} to {:?} on grid {} with {:?}", name, start, ends, seed, diagonal),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn dijkstra_finds_cheapest_path() {
+    check_optimal("Dijkstra", |_, _| true, 1, |start, ends, cost_calc, diagonal, cost_model| {
+        Box::new(Dijkstra::new_fixed_target(start, ends, cost_calc, WIDTH, HEIGHT, diagonal, cost_model))
+    });
+}
+
+#[test]
+fn dijkstra_finds_cheapest_path_to_nearest_end() {
+    check_optimal("Dijkstra", |_, _| true, 2, |start, ends, cost_calc, diagonal, cost_model| {
+        Box::new(Dijkstra::new_fixed_target(start, ends, cost_calc, WIDTH, HEIGHT, diagonal, cost_model))
+    });
+}
+
+#[test]
+fn astar_finds_cheapest_path_with_admissible_heuristics() {
+    for heuristic in (0..Heuristic::len()).map(Heuristic::from_index) {
+        let include = |diagonal, cost_model| heuristic.is_admissible(diagonal, cost_model, Combination::Min, 1);
+        check_optimal(&format!("A* ({})", heuristic.name()), include, 1, |start, ends, cost_calc, diagonal, cost_model| {
+            Box::new(Astar::new_fixed_target(start, ends, cost_calc, WIDTH, HEIGHT, diagonal, cost_model, heuristic, Combination::Min))
+        });
+    }
+}
+
+#[test]
+fn astar_finds_cheapest_path_to_nearest_end_with_min_combination() {
+    for heuristic in (0..Heuristic::len()).map(Heuristic::from_index) {
+        let include = |diagonal, cost_model| heuristic.is_admissible(diagonal, cost_model, Combination::Min, 2);
+        check_optimal(&format!("A* ({})", heuristic.name()), include, 2, |start, ends, cost_calc, diagonal, cost_model| {
+            Box::new(Astar::new_fixed_target(start, ends, cost_calc, WIDTH, HEIGHT, diagonal, cost_model, heuristic, Combination::Min))
+        });
+    }
+}
+
+#[test]
+fn weighted_astar_stays_within_its_weight_of_the_cheapest_path() {
+    for seed in 0..20 {
+        let grid = make_grid(seed);
+        let cost_calc = make_cost_calc(&grid);
+        let free: Vec<Coord> = coords().into_iter().filter(|&xy| cost_calc(xy) != NODE_WALL).collect();
+        for diagonal in (0..Diagonal::len()).map(Diagonal::from_index) {
+            for cost_model in (0..CostModel::len()).map(CostModel::from_index) {
+                let dist = all_pairs(&grid, diagonal, cost_model);
+                for (i, &start) in free.iter().enumerate().step_by(3) {
+                    let end = free[(i * 7 + 5) % free.len()];
+                    let expected = dist[index(start)][index(end)];
+                    if expected == INFINITE || expected == Cost::ZERO { continue; }
+                    for weight in [1., 1.5, 2., 4.] {
+                        let mut algo = Astar::new_weighted(start, vec![end], make_cost_calc(&grid), WIDTH, HEIGHT, diagonal, cost_model, Heuristic::suggested(diagonal, cost_model), Combination::Min, weight);
+                        let path = run(&mut algo).expect("weighted A* found no path");
+                        check_path(&grid, diagonal, start, &[end], &path);
+                        let cost = path_cost(cost_model, cost_calc.as_ref(), &path);
+                        let ratio = algo.get_cost_ratio().expect("weighted A* has a cost ratio once finished");
+                        if weight == 1. {
+                            assert_eq!(cost, expected, "weighted A* at weight 1 found a more expensive path from {} to {} on grid {} with {:?} and {:?}", start, end, seed, diagonal, cost_model);
+                            assert_eq!(ratio, 1.);
+                        } else {
+                            assert!((1. ..=weight).contains(&ratio), "weighted A* at weight {} found a path {} times the cheapest from {} to {} on grid {} with {:?} and {:?}", weight, ratio, start, end, seed, diagonal, cost_model);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn changing_the_weight_restarts_the_search() {
+    let grid = make_grid(3);
+    let cost_calc = make_cost_calc(&grid);
+    let free: Vec<Coord> = coords().into_iter().filter(|&xy| cost_calc(xy) != NODE_WALL).collect();
+    let (start, end) = (free[0], free[free.len() - 1]);
+    let mut algo = Astar::new_weighted(start, vec![end], make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Always, CostModel::Entry, Heuristic::Octile, Combination::Min, 1.);
+    for _ in 0..3 {
+        algo.tick();
+    }
+    algo.take_events();
+    algo.set_weight(3.);
+    assert_eq!(algo.get_weight(), Some(3.));
+    assert!(matches!(algo.get_data(), AlgoStatus::InProgress(_)));
+    assert!(algo.take_events().iter().any(|event| matches!(event, AlgoEvent::Restarted)), "changing the weight didn't restart the search");
+    let path = run(&mut algo).expect("no path after changing the weight");
+    check_path(&grid, Diagonal::Always, start, &[end], &path);
+    assert!(algo.get_cost_ratio().unwrap() <= 3.);
+}
+
+#[test]
+fn bfs_finds_fewest_steps() {
+    //with tile costs ignored and no diagonal steps every step costs the same, so the fewest steps is the cheapest path
+    check_optimal("BFS", |diagonal, cost_model| diagonal == Diagonal::Never && cost_model == CostModel::Ignore, 2, |start, ends, cost_calc, diagonal, _| {
+        Box::new(Bfs::new_fixed_target(start, ends, cost_calc, WIDTH, HEIGHT, diagonal))
+    });
+}
+
+#[test]
+fn greedy_finds_a_path_when_there_is_one() {
+    check_reachable("Greedy", |start, ends, cost_calc, diagonal, _| {
+        Box::new(Greedy::new_fixed_target(start, ends, cost_calc, WIDTH, HEIGHT, diagonal, Heuristic::suggested(diagonal, CostModel::Entry), Combination::Min))
+    }, |grid, diagonal, _, start, ends, path, _| check_path(grid, diagonal, start, ends, path));
+}
+
+//Theta* paths are waypoints joined by straight lines, none of which may cross a wall
+fn check_waypoints(grid: &[Vec<i32>], _: Diagonal, start: Coord, ends: &[Coord], path: &[Coord]) {
+    assert_eq!(path.first(), Some(&start));
+    assert!(ends.contains(path.last().unwrap()));
+    for pair in path.windows(2) {
+        //sampled at an odd number of steps so no sample lands exactly between two tiles
+        let samples = 97;
+        for step in 0..=samples {
+            let t = step as f32 / samples as f32;
+            let x = (pair[0].x as f32 + (pair[1].x - pair[0].x) as f32 * t).round() as usize;
+            let y = (pair[0].y as f32 + (pair[1].y - pair[0].y) as f32 * t).round() as usize;
+            assert_ne!(grid[x][y], NODE_WALL, "line from {} to {} crosses the wall at ({}, {})", pair[0], pair[1], x, y);
+        }
+    }
+}
+
+#[test]
+fn theta_finds_a_path_no_dearer_than_the_cheapest_grid_path() {
+    //a straight line never costs more than stepping along the tiles it crosses, so cutting corners can only make paths cheaper
+    check_reachable("Theta*", |start, ends, cost_calc, diagonal, cost_model| {
+        Box::new(Theta::new_fixed_target(start, ends, cost_calc, WIDTH, HEIGHT, diagonal, cost_model, Heuristic::suggested(diagonal, cost_model), Combination::Min))
+    }, |grid, diagonal, cost_model, start, ends, path, cheapest| {
+        check_waypoints(grid, diagonal, start, ends, path);
+        let cost_calc = make_cost_calc(grid);
+        let cost: Cost = path.windows(2).map(|pair| line_cost(cost_model, cost_calc.as_ref(), pair[0], pair[1])).sum();
+        assert!(cost <= cheapest, "Theta* found a path costing {} from {} to {:?} with {:?} and {:?} where a grid path costs {}", cost, start, ends, diagonal, cost_model, cheapest);
+    });
+}
+
+#[test]
+fn bidirectional_finds_cheapest_path() {
+    for end_count in 1..=2 {
+        check_optimal("Bidirectional", |_, _| true, end_count, |start, ends, cost_calc, diagonal, cost_model| {
+            Box::new(Bidirectional::new_fixed_target(start, ends, cost_calc, WIDTH, HEIGHT, diagonal, cost_model, Heuristic::suggested(diagonal, cost_model), Combination::Min))
+        });
+    }
+}
+
+#[test]
+fn ida_star_finds_cheapest_path() {
+    //IDA* tries every route under the threshold, so it's kept to narrow corridors where there aren't many
+    //The pocket at the bottom of the second one can't be reached from the rest
+    let mazes = vec![
+        grid_from_rows(["0209010", "0909090", "0300050", "9909999", "0010209"]),
+        grid_from_rows(["0000000", "9999990", "0300000", "0999999", "0090299"]),
+    ];
+    check_optimal_on("IDA*", mazes, |_, _| true, 1, |start, ends, cost_calc, diagonal, cost_model| {
+        Box::new(IdaStar::new_fixed_target(start, ends, cost_calc, WIDTH, HEIGHT, diagonal, cost_model, Heuristic::suggested(diagonal, cost_model), Combination::Min))
+    });
+}
+
+#[test]
+fn ida_star_finds_cheapest_path_on_a_shipped_map() {
+    let map = shipped_maps().into_iter().find(|map| !map.is_uniform_cost()).expect("no shipped map has tile costs");
+    let (width, height) = (map.get_column_count() as i32, map.get_row_count() as i32);
+    let cost = map.cost.clone();
+    let cost_calc = move || -> Box<CostCalc> {
+        let cost = cost.clone();
+        Box::new(move |xy: Coord| if xy.is_out_of_bounds(width, height) { -1 } else { cost[xy.x as usize][xy.y as usize] })
+    };
+    let diagonal = Diagonal::OneWall;
+    let cost_model = CostModel::Entry;
+    let dist = all_pairs_on(Rc::new(cost_calc()), width, height, diagonal, cost_model);
+    for variant in &map.variants {
+        let mut algo = IdaStar::new_fixed_target(variant.start, variant.ends.clone(), cost_calc(), width, height, diagonal, cost_model, Heuristic::suggested(diagonal, cost_model), Combination::Min);
+        let path = run(&mut algo).expect("IDA* found no path");
+        let expected = variant.ends.iter().map(|end| dist[(variant.start.x * height + variant.start.y) as usize][(end.x * height + end.y) as usize]).min().unwrap();
+        assert_eq!(path.first(), Some(&variant.start));
+        assert!(variant.ends.contains(path.last().unwrap()));
+        let shared = Rc::new(cost_calc());
+        for pair in path.windows(2) {
+            assert!(diagonal.get_neighbours(shared.clone(), pair[0]).contains(&pair[1]), "{} is not next to {}", pair[1], pair[0]);
+        }
+        assert_eq!(path_cost(cost_model, shared.as_ref(), &path), expected, "IDA* found a more expensive path from {}", variant.start);
+    }
+}
+
+fn check_cost(grid: &[Vec<i32>], diagonal: Diagonal, cost_model: CostModel, start: Coord, end: Coord, path: Option<Vec<Coord>>, when: &str) {
+    let expected = all_pairs(grid, diagonal, cost_model)[index(start)][index(end)];
+    match path {
+        Some(path) => {
+            check_path(grid, diagonal, start, &[end], &path);
+            assert_eq!(path_cost(cost_model, make_cost_calc(grid).as_ref(), &path), expected, "D* Lite found a more expensive path {} with {:?} and {:?}", when, diagonal, cost_model);
+        }
+        None => assert_eq!(expected, INFINITE, "D* Lite found no path {} with {:?} and {:?}", when, diagonal, cost_model),
+    }
+}
+
+#[test]
+fn dstar_lite_replans_when_walls_change() {
+    for seed in 0..20 {
+        for diagonal in (0..Diagonal::len()).map(Diagonal::from_index) {
+            for cost_model in (0..CostModel::len()).map(CostModel::from_index) {
+                let grid = Rc::new(RefCell::new(make_grid(seed)));
+                let free: Vec<Coord> = coords().into_iter().filter(|&xy| grid.borrow()[xy.x as usize][xy.y as usize] != NODE_WALL).collect();
+                let (start, end) = (free[0], free[free.len() - 1]);
+                let make_algo = || DStarLite::new_fixed_target(start, vec![end], make_shared_cost_calc(&grid), WIDTH, HEIGHT, diagonal, cost_model, Heuristic::suggested(diagonal, cost_model));
+
+                let mut first = make_algo();
+                let mut ticks = 0;
+                while let AlgoStatus::InProgress(_) = first.get_data() {
+                    first.tick();
+                    ticks += 1;
+                }
+                let path = match first.get_data() {
+                    AlgoStatus::Found(path, _) if path.coords().len() > 2 => path.coords().clone(),
+                    _ => continue,
+                };
+                let blocked = path[path.len() / 2];
+                let before = grid.borrow()[blocked.x as usize][blocked.y as usize];
+
+                //walled off half way through the search
+                let mut algo = make_algo();
+                for _ in 0..ticks / 2 {
+                    algo.tick();
+                }
+                grid.borrow_mut()[blocked.x as usize][blocked.y as usize] = NODE_WALL;
+                algo.cost_changed(blocked);
+                check_cost(&grid.borrow(), diagonal, cost_model, start, end, run(&mut algo), &format!("after walling {} mid-search on grid {}", blocked, seed));
+
+                //and opened again once it's finished
+                grid.borrow_mut()[blocked.x as usize][blocked.y as usize] = before;
+                algo.cost_changed(blocked);
+                check_cost(&grid.borrow(), diagonal, cost_model, start, end, run(&mut algo), &format!("after clearing {} on grid {}", blocked, seed));
+            }
+        }
+    }
+}
+
+#[test]
+fn dstar_lite_finds_no_path_once_the_end_is_walled_off() {
+    let grid = Rc::new(RefCell::new(vec![vec![0; HEIGHT as usize]; WIDTH as usize]));
+    let (start, end) = (Coord::new(0, 0), Coord::new(5, 3));
+    for diagonal in (0..Diagonal::len()).map(Diagonal::from_index) {
+        for xy in coords() {
+            grid.borrow_mut()[xy.x as usize][xy.y as usize] = 0;
+        }
+        let mut algo = DStarLite::new_fixed_target(start, vec![end], make_shared_cost_calc(&grid), WIDTH, HEIGHT, diagonal, CostModel::Entry, Heuristic::suggested(diagonal, CostModel::Entry));
+        assert!(run(&mut algo).is_some());
+        for x in -1..=1 {
+            for y in -1..=1 {
+                let xy = end + (x, y);
+                if xy == end { continue; }
+                grid.borrow_mut()[xy.x as usize][xy.y as usize] = NODE_WALL;
+                algo.cost_changed(xy);
+            }
+        }
+        assert!(run(&mut algo).is_none(), "D* Lite still found a path to a walled off end with {:?}", diagonal);
+    }
+}
+
+fn shipped_maps() -> Vec<Map> {
+    let dir = project_resources_dir().expect("not run through cargo");
+    return (0..10)
+        .map(|which| {
+            let text = fs::read_to_string(dir.join(map_file_name(which))).expect("Unreadable map file");
+            parse_map(&text, &map_file_name(which)).unwrap_or_else(|err| panic!("{}", err))
+        })
+        .collect();
+}
+
+#[test]
+fn jps_matches_astar_on_shipped_uniform_maps() {
+    let maps = shipped_maps();
+    assert!(maps.iter().any(|map| !map.is_uniform_cost()), "no shipped map has tile costs for JPS to be refused on");
+    assert!(Algo::Jps.requires_uniform_cost());
+    for map in maps.iter().filter(|map| map.is_uniform_cost()) {
+        let (width, height) = (map.get_column_count() as i32, map.get_row_count() as i32);
+        let cost = map.cost.clone();
+        let cost_calc = move || -> Box<CostCalc> {
+            let cost = cost.clone();
+            Box::new(move |xy: Coord| if xy.is_out_of_bounds(width, height) { -1 } else { cost[xy.x as usize][xy.y as usize] })
+        };
+        for variant in &map.variants {
+            for diagonal in (0..Diagonal::len()).map(Diagonal::from_index) {
+                //with every tile free the cost models only differ in how long diagonal steps are
+                for cost_model in [CostModel::Entry, CostModel::Legacy] {
+                    let heuristic = Heuristic::suggested(diagonal, cost_model);
+                    let mut jps = Jps::new_fixed_target(variant.start, variant.ends.clone(), cost_calc(), width, height, diagonal, cost_model, heuristic, Combination::Min);
+                    let mut astar = Astar::new_fixed_target(variant.start, variant.ends.clone(), cost_calc(), width, height, diagonal, cost_model, heuristic, Combination::Min);
+                    let costs = [run(&mut jps), run(&mut astar)].map(|path| path.map(|path| path_cost(cost_model, cost_calc().as_ref(), &path)));
+                    assert_eq!(costs[0], costs[1], "JPS and A* disagree from {} with {:?} and {:?}", variant.start, diagonal, cost_model);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn tour_visits_every_end_on_shipped_maps() {
+    let maps = shipped_maps();
+    assert!(maps.iter().any(|map| map.variants.iter().any(|variant| variant.ends.len() > 1)), "no shipped map has several ends to tour");
+    let mut toured = 0;
+    for map in &maps {
+        let (width, height) = (map.get_column_count() as i32, map.get_row_count() as i32);
+        let cost = map.cost.clone();
+        let cost_calc = move || -> Box<CostCalc> {
+            let cost = cost.clone();
+            Box::new(move |xy: Coord| if xy.is_out_of_bounds(width, height) { -1 } else { cost[xy.x as usize][xy.y as usize] })
+        };
+        for variant in map.variants.iter().filter(|variant| variant.ends.len() > 1) {
+            for diagonal in (0..Diagonal::len()).map(Diagonal::from_index) {
+                let cost_model = CostModel::Entry;
+                let mut tour = Tour::new_fixed_target(variant.start, variant.ends.clone(), cost_calc(), width, height, diagonal, cost_model);
+                let path = match run(&mut tour) {
+                    Some(path) => path,
+                    //a tour is only possible when every end can be reached
+                    None => continue,
+                };
+                for end in &variant.ends {
+                    assert!(path.contains(end), "tour from {} with {:?} never reaches {}", variant.start, diagonal, end);
+                }
+                let legs: Vec<Vec<Coord>> = tour.get_legs().expect("Tour has legs").iter().map(|leg| leg.coords().clone()).collect();
+                assert_eq!(legs.len(), variant.ends.len());
+                assert_eq!(legs[0][0], variant.start);
+                for pair in legs.windows(2) {
+                    assert_eq!(pair[1][0], *pair[0].last().unwrap(), "leg doesn't start where the one before it ended");
+                }
+                let shared = Rc::new(cost_calc());
+                for pair in path.windows(2) {
+                    assert!(diagonal.get_neighbours(shared.clone(), pair[0]).contains(&pair[1]), "{} is not next to {}", pair[1], pair[0]);
+                }
+                let leg_costs: Cost = legs.iter().map(|leg| path_cost(cost_model, shared.as_ref(), leg)).sum();
+                assert_eq!(leg_costs, path_cost(cost_model, shared.as_ref(), &path), "tour from {} with {:?} costs more than its legs", variant.start, diagonal);
+                toured += 1;
+            }
+        }
+    }
+    assert!(toured > 0, "no shipped map has a tour that reaches every end");
+}
+
+#[test]
+fn only_min_combination_is_admissible_with_several_ends() {
+    for diagonal in (0..Diagonal::len()).map(Diagonal::from_index) {
+        assert!(Heuristic::Chebyshev.is_admissible(diagonal, CostModel::Entry, Combination::Min, 2));
+        assert!(!Heuristic::Chebyshev.is_admissible(diagonal, CostModel::Entry, Combination::Max, 2));
+        assert!(!Heuristic::Chebyshev.is_admissible(diagonal, CostModel::Entry, Combination::Sum, 2));
+        assert!(Heuristic::Chebyshev.is_admissible(diagonal, CostModel::Entry, Combination::Sum, 1));
+    }
+}
+
+#[test]
+fn suggested_heuristic_is_admissible() {
+    for diagonal in (0..Diagonal::len()).map(Diagonal::from_index) {
+        for cost_model in (0..CostModel::len()).map(CostModel::from_index) {
+            let heuristic = Heuristic::suggested(diagonal, cost_model);
+            assert!(heuristic.is_admissible(diagonal, cost_model, Combination::Min, 1), "{} is not admissible with {:?} and {:?}", heuristic.name(), diagonal, cost_model);
+        }
+    }
+}
+
+#[test]
+fn straight_line_nudge_never_overestimates() {
+    for seed in 0..10 {
+        let grid = make_grid(seed);
+        for diagonal in (0..Diagonal::len()).map(Diagonal::from_index) {
+            for cost_model in (0..CostModel::len()).map(CostModel::from_index) {
+                if !Heuristic::OctileStraight.is_admissible(diagonal, cost_model, Combination::Min, 1) { continue; }
+                let dist = all_pairs(&grid, diagonal, cost_model);
+                for origin in coords().into_iter().step_by(4) {
+                    for current in coords() {
+                        for end in coords() {
+                            let h = Heuristic::OctileStraight.calc_fixed(&origin, &current, &end);
+                            assert!(h <= dist[index(current)][index(end)], "{} from {} to {} overestimates on grid {} with {:?}", h, current, end, seed, diagonal);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    //on a big empty map the cheapest path is the octile distance, far from the straight line the nudge still stays under it
+    let (origin, end) = (Coord::new(0, 0), Coord::new(255, 200));
+    for current in [Coord::new(255, 0), Coord::new(0, 255), Coord::new(128, 3), Coord::new(254, 200)] {
+        let octile = Heuristic::Octile.calc_fixed(&origin, &current, &end);
+        assert!(Heuristic::OctileStraight.calc_fixed(&origin, &current, &end) <= octile);
+    }
+    assert_eq!(Heuristic::OctileStraight.calc_fixed(&origin, &end, &end), Cost::ZERO);
+}
+
+#[test]
+fn path_costs_are_shown_exactly() {
+    let grid = vec![vec![0; HEIGHT as usize]; WIDTH as usize];
+    let cost_calc = make_cost_calc(&grid);
+    let path = [Coord::new(0, 0), Coord::new(1, 1), Coord::new(2, 2), Coord::new(2, 3)];
+    assert_eq!(path_cost(CostModel::Ignore, cost_calc.as_ref(), &path).to_string(), "3.8286");
+    assert_eq!(path_cost(CostModel::Legacy, cost_calc.as_ref(), &path).to_string(), "3");
+}
+
+#[test]
+fn replaying_astar_events_gives_the_same_result() {
+    for seed in 0..20 {
+        let grid = make_grid(seed);
+        let cost_calc = make_cost_calc(&grid);
+        let free: Vec<Coord> = coords().into_iter().filter(|&xy| cost_calc(xy) != NODE_WALL).collect();
+        let (start, end) = (free[0], free[free.len() - 1]);
+        let mut astar = Astar::new_fixed_target(start, vec![end], make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Always, CostModel::Entry, Heuristic::Octile, Combination::Min);
+        let mut tracker = SearchTracker::default();
+        loop {
+            astar.tick();
+            astar.take_events().iter().for_each(|event| tracker.apply(event));
+            match (astar.get_data(), tracker.status()) {
+                (AlgoStatus::InProgress(_), AlgoStatus::InProgress(_)) => {}
+                (AlgoStatus::Found(path, _), AlgoStatus::Found(replayed, _)) => {
+                    assert_eq!(path.coords(), replayed.coords());
+                    break;
+                }
+                (AlgoStatus::NoPath, AlgoStatus::NoPath) => break,
+                _ => panic!("replayed status differs on grid {}", seed),
+            }
+        }
+    }
+}
+
+#[test]
+fn every_algorithm_reports_everything_through_events() {
+    for seed in 0..20 {
+        //JPS needs every free tile to cost the same
+        let grid: Vec<Vec<i32>> = make_grid(seed).into_iter()
+            .map(|column| column.into_iter().map(|cost| if cost == NODE_WALL { NODE_WALL } else { 0 }).collect())
+            .collect();
+        let cost_calc = make_cost_calc(&grid);
+        let free: Vec<Coord> = coords().into_iter().filter(|&xy| cost_calc(xy) != NODE_WALL).collect();
+        let (start, ends) = (free[0], vec![free[free.len() - 1]]);
+        let heuristic = Heuristic::Octile;
+        let algos: Vec<Box<dyn Algorithm>> = vec![
+            Box::new(Bfs::new_fixed_target(start, ends.clone(), make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Always)),
+            Box::new(Greedy::new_fixed_target(start, ends.clone(), make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Always, heuristic, Combination::Min)),
+            Box::new(Jps::new_fixed_target(start, ends.clone(), make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Always, CostModel::Entry, heuristic, Combination::Min)),
+            Box::new(Theta::new_fixed_target(start, ends.clone(), make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Always, CostModel::Entry, heuristic, Combination::Min)),
+            Box::new(Bidirectional::new_fixed_target(start, ends.clone(), make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Always, CostModel::Entry, heuristic, Combination::Min)),
+            Box::new(DStarLite::new_fixed_target(start, ends.clone(), make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Always, CostModel::Entry, heuristic)),
+            Box::new(IdaStar::new_fixed_target(start, ends.clone(), make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Always, CostModel::Entry, heuristic, Combination::Min)),
+        ];
+        for mut algo in algos {
+            let mut tracker = SearchTracker::default();
+            loop {
+                algo.tick();
+                algo.take_events().iter().for_each(|event| tracker.apply(event));
+                match (algo.get_data(), tracker.status()) {
+                    (AlgoStatus::InProgress(_), AlgoStatus::InProgress(_)) => {}
+                    (AlgoStatus::Found(path, frontiers), AlgoStatus::Found(replayed, replayed_frontiers)) => {
+                        assert_eq!(path.coords(), replayed.coords());
+                        //IDA* and D* Lite only report the path once they've found it
+                        if frontiers.is_empty() {
+                            break;
+                        }
+                        //every tile the search closed or jumped over is closed in the replay
+                        assert_eq!(frontiers.len(), replayed_frontiers.len());
+                        for (frontier, replayed) in frontiers.iter().zip(replayed_frontiers) {
+                            let still_closed = frontier.closed.iter().copied().filter(|xy| !replayed.open.contains(xy)).collect();
+                            let (_, closed) = sorted_frontier(&AlgoStatus::InProgress(vec![Frontier::new(vec![], still_closed)]));
+                            let (_, replayed_closed) = sorted_frontier(&AlgoStatus::InProgress(vec![replayed]));
+                            assert_eq!(closed, replayed_closed, "replayed closed tiles differ on grid {}", seed);
+                        }
+                        break;
+                    }
+                    (AlgoStatus::NoPath, AlgoStatus::NoPath) => break,
+                    _ => panic!("replayed status differs on grid {}", seed),
+                }
+            }
+        }
+    }
+}
+
+fn sorted_frontier(status: &AlgoStatus) -> (Vec<(i32, i32)>, Vec<(i32, i32)>) {
+    let frontiers = match status {
+        AlgoStatus::InProgress(frontiers) | AlgoStatus::Found(_, frontiers) => frontiers.clone(),
+        AlgoStatus::NoPath => vec![],
+    };
+    let mut open: Vec<(i32, i32)> = frontiers.iter().flat_map(|frontier| frontier.open.iter().map(|&xy| xy.into())).collect();
+    let mut closed: Vec<(i32, i32)> = frontiers.iter().flat_map(|frontier| frontier.closed.iter().map(|&xy| xy.into())).collect();
+    open.sort();
+    closed.sort();
+    return (open, closed);
+}
+
+#[test]
+fn scrubbing_back_shows_each_tick_as_it_was() {
+    let grid = make_grid(3);
+    let cost_calc = make_cost_calc(&grid);
+    let free: Vec<Coord> = coords().into_iter().filter(|&xy| cost_calc(xy) != NODE_WALL).collect();
+    let mut algo: Box<dyn Algorithm> = Box::new(Dijkstra::new_fixed_target(free[0], vec![free[free.len() - 1]], make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Never, CostModel::Entry));
+    let mut timeline = Timeline::new(algo.as_mut());
+    let mut seen = vec![sorted_frontier(&timeline.tracker_at(0).status())];
+    while let AlgoStatus::InProgress(_) = algo.get_data() {
+        algo.tick();
+        timeline.record(algo.as_mut());
+        seen.push(sorted_frontier(&timeline.tracker_at(timeline.last_tick()).status()));
+    }
+    let mut tracker = timeline.tracker_at(timeline.last_tick());
+    let mut shown = timeline.last_tick();
+    for tick in (0..timeline.last_tick()).rev().step_by(2).chain(1..timeline.last_tick()) {
+        timeline.replay(&mut tracker, shown, tick);
+        shown = tick;
+        assert_eq!(sorted_frontier(&tracker.status()), seen[tick], "tick {} differs", tick);
+    }
+}
+
+#[test]
+fn long_searches_are_rebuilt_from_checkpoints() {
+    let map = shipped_maps().remove(0);
+    let (width, height) = (map.get_column_count() as i32, map.get_row_count() as i32);
+    let cost_calc = || -> Box<CostCalc> {
+        let cost = map.cost.clone();
+        Box::new(move |xy: Coord| if xy.is_out_of_bounds(width, height) { -1 } else { cost[xy.x as usize][xy.y as usize] })
+    };
+    let variant = &map.variants[0];
+    let heuristic = Heuristic::suggested(Diagonal::Always, CostModel::Entry);
+    let algos: Vec<Box<dyn Algorithm>> = vec![
+        Box::new(Bidirectional::new_fixed_target(variant.start, variant.ends.clone(), cost_calc(), width, height, Diagonal::Always, CostModel::Entry, heuristic, Combination::Min)),
+        Box::new(Dijkstra::new_fixed_target(variant.start, variant.ends.clone(), cost_calc(), width, height, Diagonal::Always, CostModel::Entry)),
+    ];
+    for mut algo in algos {
+        let mut timeline = Timeline::new(algo.as_mut());
+        let mut seen = vec![];
+        let shown = |timeline: &Timeline, tick: usize| sorted_frontier(&timeline.tracker_at(tick).status());
+        seen.push(shown(&timeline, 0));
+        while let AlgoStatus::InProgress(_) = algo.get_data() {
+            algo.tick();
+            timeline.record(algo.as_mut());
+            seen.push(shown(&timeline, timeline.last_tick()));
+        }
+        //enough ticks for the checkpoints to have been thinned out more than once
+        assert!(timeline.last_tick() > 128, "only {} ticks", timeline.last_tick());
+        let mut tracker = timeline.tracker_at(0);
+        let mut from = 0;
+        for tick in (0..=timeline.last_tick()).step_by(3).chain((0..timeline.last_tick()).rev().step_by(7)) {
+            assert_eq!(shown(&timeline, tick), seen[tick], "tick {} differs", tick);
+            timeline.replay(&mut tracker, from, tick);
+            assert_eq!(sorted_frontier(&tracker.status()), seen[tick], "replaying to tick {} differs", tick);
+            from = tick;
+        }
+    }
+}
+
+#[test]
+fn walls_changed_between_ticks_only_change_the_latest_tick() {
+    let grid = Rc::new(RefCell::new(make_grid(0)));
+    let free: Vec<Coord> = coords().into_iter().filter(|&xy| grid.borrow()[xy.x as usize][xy.y as usize] != NODE_WALL).collect();
+    let (start, end) = (free[0], free[free.len() - 1]);
+    let make_algo = || DStarLite::new_fixed_target(start, vec![end], make_shared_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Always, CostModel::Entry, Heuristic::Octile);
+    let (mut algo, mut twin) = (make_algo(), make_algo());
+    //the twin's events are applied straight to a tracker to compare the timeline with
+    let mut live = SearchTracker::default();
+    twin.take_events().iter().for_each(|event| live.apply(event));
+    let mut timeline = Timeline::new(&mut algo);
+    let mut seen = vec![sorted_frontier(&timeline.tracker_at(0).status())];
+    for _ in 0..4 {
+        algo.tick();
+        twin.tick();
+        timeline.record(&mut algo);
+        twin.take_events().iter().for_each(|event| live.apply(event));
+        seen.push(sorted_frontier(&timeline.tracker_at(timeline.last_tick()).status()));
+    }
+    let walled = free[1];
+    grid.borrow_mut()[walled.x as usize][walled.y as usize] = NODE_WALL;
+    algo.cost_changed(walled);
+    twin.cost_changed(walled);
+    timeline.update_latest(&mut algo);
+    twin.take_events().iter().for_each(|event| live.apply(event));
+    let last = timeline.last_tick();
+    assert_eq!(sorted_frontier(&timeline.tracker_at(last).status()), sorted_frontier(&live.status()));
+    for (tick, frontier) in seen.iter().enumerate().take(last) {
+        assert_eq!(&sorted_frontier(&timeline.tracker_at(tick).status()), frontier, "tick {} changed", tick);
+    }
+    let mut tracker = timeline.tracker_at(0);
+    timeline.replay(&mut tracker, 0, last);
+    assert_eq!(sorted_frontier(&tracker.status()), sorted_frontier(&live.status()));
+}
+
+#[test]
+fn tiles_keep_the_f_they_were_opened_with() {
+    let grid = make_grid(5);
+    let cost_calc = make_cost_calc(&grid);
+    let free: Vec<Coord> = coords().into_iter().filter(|&xy| cost_calc(xy) != NODE_WALL).collect();
+    let mut algo = Astar::new_weighted(free[0], vec![free[free.len() - 1]], make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Always, CostModel::Entry, Heuristic::Octile, Combination::Min, 2.);
+    let mut timeline = Timeline::new(&mut algo);
+    for _ in 0..3 {
+        algo.tick();
+        timeline.record(&mut algo);
+    }
+    let before = timeline.last_tick();
+    algo.set_weight(3.);
+    timeline.record(&mut algo);
+    algo.tick();
+    timeline.record(&mut algo);
+    //scrubbing back shows the weight tiles were opened with, not the current one
+    for (tick, weight) in [(before, 2.), (timeline.last_tick(), 3.)] {
+        let tracker = timeline.tracker_at(tick);
+        let tiles: Vec<_> = coords().into_iter().flat_map(|xy| tracker.tiles(xy)).map(|(_, tile)| *tile).collect();
+        assert!(tiles.len() > 1, "nothing opened by tick {}", tick);
+        for tile in tiles.iter().filter(|tile| tile.parent.is_some()) {
+            assert_eq!(tile.f, tile.g + tile.h.scale(weight), "tick {}", tick);
+        }
+    }
+}
+
+#[test]
+fn tiles_remember_the_ticks_they_were_opened_and_closed() {
+    let grid = make_grid(4);
+    let cost_calc = make_cost_calc(&grid);
+    let free: Vec<Coord> = coords().into_iter().filter(|&xy| cost_calc(xy) != NODE_WALL).collect();
+    let mut algo: Box<dyn Algorithm> = Box::new(Dijkstra::new_fixed_target(free[0], vec![free[free.len() - 1]], make_cost_calc(&grid), WIDTH, HEIGHT, Diagonal::Never, CostModel::Entry));
+    let mut timeline = Timeline::new(algo.as_mut());
+    while let AlgoStatus::InProgress(_) = algo.get_data() {
+        algo.tick();
+        timeline.record(algo.as_mut());
+    }
+    let last = timeline.tracker_at(timeline.last_tick());
+    for tick in 1..=timeline.last_tick() {
+        let tracker = timeline.tracker_at(tick);
+        if let Some((xy, tile)) = tracker.last_closed().filter(|(_, tile)| tile.closed_tick == Some(tick)) {
+            let (_, final_tile) = last.tiles(xy)[0];
+            assert!(tile.opened_tick < tick, "{} closed on tick {} before being opened", xy, tick);
+            assert_eq!(final_tile.opened_tick, tile.opened_tick);
+            assert_eq!(final_tile.closed_tick, Some(tick));
+        }
+    }
+    assert_eq!(last.tiles(free[0])[0].1.opened_tick, 0);
+}
@@ -0,0 +1,174 @@
+use std::rc::Rc;
+use crate::data::Coord;
+use crate::data::diagonal::Diagonal;
+use crate::data::heuristic::{Heuristic, Combination};
+use crate::data::cost::Cost;
+use crate::data::cost_model::CostModel;
+use crate::algos::node_arena::{NodeArena, Membership};
+use crate::algos::{Algorithm, AlgoStatus, AlgoEvent, Frontier, Path, CostCalc, DIAGONAL_FACTOR};
+use crate::algos::AlgoStatus::*;
+
+//Any angle A*, a node's parent can be any node it has line of sight to rather than just a neighbour
+pub struct Theta {
+    diagonal: Diagonal,
+    cost_model: CostModel,
+    width: i32,
+    height: i32,
+    nodes: NodeArena,
+    cost_calc: Rc<Box<CostCalc>>,
+    start: Coord,
+    ends: Vec<Coord>,
+    heuristic: Heuristic,
+    combination: Combination,
+    status: AlgoStatus,
+}
+
+impl Theta {
+    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal, cost_model: CostModel, heuristic: Heuristic, combination: Combination) -> Theta {
+        let mut nodes = NodeArena::new(width, height);
+        nodes.open(start, None, Cost::ZERO, Cost::ZERO, Cost::ZERO);
+        Theta {
+            width,
+            height,
+            diagonal,
+            cost_model,
+            nodes,
+            cost_calc: Rc::new(cost_calc),
+            start,
+            ends,
+            heuristic,
+            combination,
+            status: AlgoStatus::InProgress(vec![]),
+        }
+    }
+}
+
+//Straight line distance, Legacy diagonal steps are as long as cardinal ones so lines are measured the same way
+fn line_length(cost_model: CostModel, from: Coord, to: Coord) -> f64 {
+    let diff = to - from;
+    if cost_model.diagonal_factor() < DIAGONAL_FACTOR {
+        return diff.x.abs().max(diff.y.abs()) as f64;
+    }
+    return ((diff.x * diff.x + diff.y * diff.y) as f64).sqrt();
+}
+
+//Every step of the tiles the line between the centres of `from` and `to` touches, in order
+//When the line passes exactly through a corner the step is diagonal and comes with the two tiles beside it
+fn line_steps(from: Coord, to: Coord) -> Vec<(Coord, Coord, Option<[Coord; 2]>)> {
+    let dx = (to.x - from.x).abs();
+    let dy = (to.y - from.y).abs();
+    let sx = (to.x - from.x).signum();
+    let sy = (to.y - from.y).signum();
+    let mut steps = vec![];
+    let mut current = from;
+    let mut ix = 0;
+    let mut iy = 0;
+    while ix < dx || iy < dy {
+        let decision = (1 + 2 * ix) * dy - (1 + 2 * iy) * dx;
+        let (next, beside) = if decision == 0 {
+            ix += 1;
+            iy += 1;
+            (current + (sx, sy), Some([current + (sx, 0), current + (0, sy)]))
+        } else if decision < 0 {
+            ix += 1;
+            (current + (sx, 0), None)
+        } else {
+            iy += 1;
+            (current + (0, sy), None)
+        };
+        steps.push((current, next, beside));
+        current = next;
+    }
+    return steps;
+}
+
+//Cost of following the line from `from` to `to`
+//Each tile crossed is charged through the cost model as if stepped into, then the total is scaled down to the line's length
+//Rounded down so a line is never dearer than stepping along the tiles it crosses
+pub fn line_cost(cost_model: CostModel, cost_calc: &CostCalc, from: Coord, to: Coord) -> Cost {
+    let (walked, cost) = line_steps(from, to).iter().fold((Cost::ZERO, Cost::ZERO), |(walked, cost), &(step_from, step_to, _)| {
+        (walked + CostModel::Ignore.step_cost(cost_calc, step_from, step_to), cost + cost_model.step_cost(cost_calc, step_from, step_to))
+    });
+    if walked == Cost::ZERO {
+        return Cost::ZERO;
+    }
+    return Cost::floor(line_length(cost_model, from, to) * cost.as_tiles() / walked.as_tiles());
+}
+
+impl Theta {
+    fn is_blocked(&self, xy: Coord) -> bool {
+        xy.is_out_of_bounds(self.width, self.height) || (self.cost_calc)(xy) < 0
+    }
+
+    //When the line passes exactly through a corner both tiles beside it must be free
+    fn has_line_of_sight(&self, from: Coord, to: Coord) -> bool {
+        return line_steps(from, to).iter().all(|&(_, next, beside)| {
+            !self.is_blocked(next) && beside.is_none_or(|beside| beside.iter().all(|&xy| !self.is_blocked(xy)))
+        });
+    }
+
+    fn process_once(&mut self) {
+        let current = match self.nodes.pop() {
+            Some(xy) => xy,
+            None => {
+                self.nodes.record(AlgoEvent::NoPath);
+                self.status = NoPath;
+                return;
+            }
+        };
+
+        if self.ends.contains(&current) {
+            let path = Path::Waypoints(self.nodes.path_to(current));
+            self.nodes.record(AlgoEvent::Finished(path.clone()));
+            self.status = Found(path, vec![Frontier::new(vec![], self.nodes.closed_coords())]);
+            return;
+        }
+
+        let grandparent = self.nodes.parent(current);
+        for new_pos in self.diagonal.get_neighbours(self.cost_calc.clone(), current) {
+            if new_pos.is_out_of_bounds(self.width, self.height) { continue; }
+
+            if (self.cost_calc)(new_pos) < 0 { continue; }
+
+            let existing = self.nodes.get(new_pos);
+            if existing.membership == Membership::Closed { continue; }
+
+            //the straight line from the grandparent is kept only when it's no dearer than going through `current`
+            let through_current = (current, self.nodes.get(current).g + self.line_cost(current, new_pos));
+            let (parent, g) = match grandparent {
+                Some(grandparent) if self.has_line_of_sight(grandparent, new_pos) => {
+                    let through_grandparent = (grandparent, self.nodes.get(grandparent).g + self.line_cost(grandparent, new_pos));
+                    if through_grandparent.1 <= through_current.1 { through_grandparent } else { through_current }
+                }
+                _ => through_current,
+            };
+            if existing.g <= g { continue; }
+
+            let h = self.heuristic.calc_multiple(&self.start, &new_pos, &self.ends, self.combination);
+            self.nodes.open(new_pos, Some(parent), g, h, g + h);
+        }
+    }
+
+    fn line_cost(&self, from: Coord, to: Coord) -> Cost {
+        return line_cost(self.cost_model, self.cost_calc.as_ref(), from, to);
+    }
+}
+
+impl Algorithm for Theta {
+    fn tick(&mut self) {
+        match self.status {
+            AlgoStatus::InProgress(_) => self.process_once(),
+            _ => {
+                //do nothing
+            }
+        }
+    }
+
+    fn get_data(&self) -> &AlgoStatus {
+        return &self.status;
+    }
+
+    fn take_events(&mut self) -> Vec<AlgoEvent> {
+        return self.nodes.take_events();
+    }
+}
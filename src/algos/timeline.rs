@@ -0,0 +1,92 @@
+use crate::algos::{Algorithm, AlgoEvent};
+use crate::algos::search_tracker::SearchTracker;
+
+//Checkpoints kept before every other one is dropped, replaying never goes further back than the gap between two
+const MAX_CHECKPOINTS: usize = 32;
+
+//Every tick of a search so earlier ones can be shown again
+//Each frame is the events of one tick, the first is the state before any ticks
+pub struct Timeline {
+    frames: Vec<Vec<AlgoEvent>>,
+    //the search at every `checkpoint_interval`th tick, earlier ticks are rebuilt from the closest one before them
+    checkpoints: Vec<(usize, SearchTracker)>,
+    checkpoint_interval: usize,
+    //the search after the latest tick
+    latest: SearchTracker,
+}
+
+fn track(tracker: &mut SearchTracker, tick: usize, events: &[AlgoEvent]) {
+    tracker.set_tick(tick);
+    events.iter().for_each(|event| tracker.apply(event));
+}
+
+impl Timeline {
+    pub fn new(algo: &mut dyn Algorithm) -> Timeline {
+        let mut timeline = Timeline { frames: vec![], checkpoints: vec![], checkpoint_interval: 1, latest: SearchTracker::default() };
+        timeline.push(algo.take_events());
+        return timeline;
+    }
+
+    //Adds the events of the latest tick
+    pub fn record(&mut self, algo: &mut dyn Algorithm) {
+        self.push(algo.take_events());
+    }
+
+    //Adds changes made outside of a tick, such as a tile cost changing, to the latest frame
+    pub fn update_latest(&mut self, algo: &mut dyn Algorithm) {
+        let events = algo.take_events();
+        let tick = self.last_tick();
+        track(&mut self.latest, tick, &events);
+        if let Some((_, checkpoint)) = self.checkpoints.last_mut().filter(|(checkpoint, _)| *checkpoint == tick) {
+            track(checkpoint, tick, &events);
+        }
+        self.frames.last_mut().expect("Timeline is never empty").extend(events);
+    }
+
+    fn push(&mut self, events: Vec<AlgoEvent>) {
+        let tick = self.frames.len();
+        track(&mut self.latest, tick, &events);
+        self.frames.push(events);
+        if tick.is_multiple_of(self.checkpoint_interval) {
+            self.checkpoints.push((tick, self.latest.clone()));
+            if self.checkpoints.len() > MAX_CHECKPOINTS {
+                self.checkpoint_interval *= 2;
+                let interval = self.checkpoint_interval;
+                self.checkpoints.retain(|(checkpoint, _)| checkpoint.is_multiple_of(interval));
+            }
+        }
+    }
+
+    pub fn last_tick(&self) -> usize {
+        self.frames.len() - 1
+    }
+
+    //The search as it was after `tick` ticks
+    pub fn tracker_at(&self, tick: usize) -> SearchTracker {
+        if tick == self.last_tick() {
+            return self.latest.clone();
+        }
+        let (checkpoint, tracker) = self.checkpoints.iter().rev()
+            .find(|(checkpoint, _)| *checkpoint <= tick)
+            .expect("Tick 0 is always a checkpoint");
+        let mut tracker = tracker.clone();
+        self.apply_frames(&mut tracker, checkpoint + 1, tick);
+        return tracker;
+    }
+
+    //Moves a tracker showing tick `from` on to tick `to`, going backwards starts again from the closest checkpoint
+    pub fn replay(&self, tracker: &mut SearchTracker, from: usize, to: usize) {
+        let checkpoint = to - to % self.checkpoint_interval;
+        if to < from || checkpoint > from {
+            *tracker = self.tracker_at(to);
+        } else {
+            self.apply_frames(tracker, from + 1, to);
+        }
+    }
+
+    fn apply_frames(&self, tracker: &mut SearchTracker, first: usize, last: usize) {
+        for (tick, events) in self.frames.iter().enumerate().take(last + 1).skip(first) {
+            track(tracker, tick, events);
+        }
+    }
+}
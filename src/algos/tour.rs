@@ -0,0 +1,115 @@
+use std::rc::Rc;
+use crate::data::Coord;
+use crate::data::diagonal::Diagonal;
+use crate::data::cost_model::CostModel;
+use crate::algos::{Algorithm, AlgoStatus, AlgoEvent, Frontier, Path, CostCalc};
+use crate::algos::dijkstra::Dijkstra;
+use crate::algos::AlgoStatus::*;
+
+//Visits every end rather than stopping at the first one reached
+//Each leg is a Dijkstra search from the last end reached to all the remaining ends,
+//so the next end visited is always the cheapest one to get to (greedy nearest next)
+pub struct Tour {
+    diagonal: Diagonal,
+    cost_model: CostModel,
+    width: i32,
+    height: i32,
+    remaining: Vec<Coord>,
+    leg: Dijkstra,
+    legs: Vec<Path>,
+    //closed nodes of each finished leg
+    leg_frontiers: Vec<Frontier>,
+    cost_calc: Rc<Box<CostCalc>>,
+    //the current leg's events, each new leg starts with Restarted
+    events: Vec<AlgoEvent>,
+    status: AlgoStatus,
+}
+
+impl Tour {
+    pub fn new_fixed_target(start: Coord, ends: Vec<Coord>, cost_calc: Box<CostCalc>, width: i32, height: i32, diagonal: Diagonal, cost_model: CostModel) -> Tour {
+        let cost_calc = Rc::new(cost_calc);
+        Tour {
+            width,
+            height,
+            diagonal,
+            cost_model,
+            leg: Dijkstra::new_fixed_target(start, ends.clone(), share_cost_calc(&cost_calc), width, height, diagonal, cost_model),
+            remaining: ends,
+            legs: vec![],
+            leg_frontiers: vec![],
+            cost_calc,
+            events: vec![],
+            status: AlgoStatus::InProgress(vec![]),
+        }
+    }
+}
+
+fn share_cost_calc(cost_calc: &Rc<Box<CostCalc>>) -> Box<CostCalc> {
+    let cost_calc = cost_calc.clone();
+    return Box::new(move |xy| cost_calc(xy));
+}
+
+impl Tour {
+    fn process_once(&mut self) {
+        self.leg.tick();
+        //the leg finishing isn't the tour finishing
+        let leg_events = self.leg.take_events().into_iter()
+            .filter(|event| !matches!(event, AlgoEvent::Finished(_) | AlgoEvent::NoPath));
+        self.events.extend(leg_events);
+        match self.leg.get_data() {
+            InProgress(_) => {}
+            Found(path, frontiers) => {
+                let path = path.coords().clone();
+                let reached = *path.last().unwrap();
+                self.leg_frontiers.extend(frontiers.iter().cloned());
+                self.legs.push(Path::Tiles(path));
+                self.remaining.retain(|end| end != &reached);
+                if self.remaining.is_empty() {
+                    self.finish();
+                } else {
+                    self.leg = Dijkstra::new_fixed_target(reached, self.remaining.clone(), share_cost_calc(&self.cost_calc), self.width, self.height, self.diagonal, self.cost_model);
+                    self.events.push(AlgoEvent::Restarted);
+                    self.status = InProgress(vec![]);
+                }
+            }
+            NoPath => {
+                self.events.push(AlgoEvent::NoPath);
+                self.status = NoPath;
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        let mut tour = vec![];
+        for leg in &self.legs {
+            //each leg starts where the previous one ended
+            let skip = if tour.is_empty() { 0 } else { 1 };
+            tour.extend(leg.coords().iter().skip(skip));
+        }
+        self.events.push(AlgoEvent::Finished(Path::Tiles(tour.clone())));
+        self.status = Found(Path::Tiles(tour), self.leg_frontiers.clone());
+    }
+}
+
+impl Algorithm for Tour {
+    fn tick(&mut self) {
+        match self.status {
+            AlgoStatus::InProgress(_) => self.process_once(),
+            _ => {
+                //do nothing
+            }
+        }
+    }
+
+    fn get_data(&self) -> &AlgoStatus {
+        return &self.status;
+    }
+
+    fn get_legs(&self) -> Option<&Vec<Path>> {
+        return Some(&self.legs);
+    }
+
+    fn take_events(&mut self) -> Vec<AlgoEvent> {
+        return std::mem::take(&mut self.events);
+    }
+}
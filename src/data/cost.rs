@@ -0,0 +1,91 @@
+use std::ops::{Add, Sub, Mul, Div};
+use std::iter::Sum;
+use std::fmt::{Display, Formatter};
+use std::fmt;
+
+//How many parts a tile is split into, costs are whole numbers of these so adding them up is exact
+pub const COST_SCALE: i64 = 10_000;
+
+//Fixed point cost in ten thousandths of a tile, a free cardinal step costs one tile
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cost(i64);
+
+impl Cost {
+    pub const ZERO: Cost = Cost(0);
+    //Larger than any path, with room to add a few together without overflowing
+    pub const INFINITE: Cost = Cost(i64::MAX / 4);
+
+    pub fn tiles(tiles: i32) -> Cost {
+        Cost(tiles as i64 * COST_SCALE)
+    }
+
+    //Nearest cost that isn't more than `tiles`
+    pub fn floor(tiles: f64) -> Cost {
+        Cost((tiles * COST_SCALE as f64).floor() as i64)
+    }
+
+    //Nearest cost that isn't less than `tiles`
+    pub fn ceil(tiles: f64) -> Cost {
+        Cost((tiles * COST_SCALE as f64).ceil() as i64)
+    }
+
+    pub fn as_tiles(&self) -> f64 {
+        self.0 as f64 / COST_SCALE as f64
+    }
+
+    pub fn scale(self, factor: f32) -> Cost {
+        Cost((self.0 as f64 * factor as f64).round() as i64)
+    }
+}
+
+impl Add for Cost {
+    type Output = Cost;
+
+    fn add(self, rhs: Cost) -> Self::Output {
+        Cost(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Cost {
+    type Output = Cost;
+
+    fn sub(self, rhs: Cost) -> Self::Output {
+        Cost(self.0 - rhs.0)
+    }
+}
+
+impl Mul<i32> for Cost {
+    type Output = Cost;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Cost(self.0 * rhs as i64)
+    }
+}
+
+impl Div<i32> for Cost {
+    type Output = Cost;
+
+    fn div(self, rhs: i32) -> Self::Output {
+        Cost(self.0 / rhs as i64)
+    }
+}
+
+impl Sum for Cost {
+    fn sum<I: Iterator<Item=Cost>>(iter: I) -> Self {
+        iter.fold(Cost::ZERO, |total, cost| total + cost)
+    }
+}
+
+//Written out exactly in tiles, without trailing zeros
+impl Display for Cost {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let whole = (self.0 / COST_SCALE).abs();
+        let fraction = (self.0 % COST_SCALE).abs();
+        if fraction == 0 {
+            return write!(f, "{}{}", sign, whole);
+        }
+        let digits = format!("{:04}", fraction);
+        return write!(f, "{}{}.{}", sign, whole, digits.trim_end_matches('0'));
+    }
+}
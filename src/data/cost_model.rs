@@ -0,0 +1,63 @@
+use crate::data::Coord;
+use crate::data::cost::Cost;
+use crate::algos::{CostCalc, DIAGONAL_FACTOR, diagonal_step_cost};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostModel {
+    Ignore,
+    Entry,
+    Average,
+    Legacy,
+}
+
+impl CostModel {
+    pub fn name(&self) -> String {
+        return match self {
+            CostModel::Ignore => String::from("Ignore tile costs"),
+            CostModel::Entry => String::from("Cost of tile entered"),
+            CostModel::Average => String::from("Average of both tiles"),
+            CostModel::Legacy => String::from("Legacy (x5 penalty)"),
+        };
+    }
+
+    pub fn len() -> usize {
+        4
+    }
+
+    pub fn from_index(idx: usize) -> CostModel {
+        return match idx {
+            0 => CostModel::Ignore,
+            1 => CostModel::Entry,
+            2 => CostModel::Average,
+            3 => CostModel::Legacy,
+            _ => panic!("Invalid index: {}", idx),
+        };
+    }
+}
+
+impl CostModel {
+    //How much longer a diagonal step is than a cardinal one
+    pub fn diagonal_factor(&self) -> f32 {
+        match self {
+            CostModel::Legacy => 1.,
+            _ => DIAGONAL_FACTOR,
+        }
+    }
+
+    //Cost of moving from `from` into the neighbouring tile `to`
+    //The distance moved is multiplied by the terrain cost, a free tile costing one
+    pub fn step_cost(&self, cost_calc: &CostCalc, from: Coord, to: Coord) -> Cost {
+        let distance = if from.x != to.x && from.y != to.y {
+            diagonal_step_cost(self.diagonal_factor())
+        } else {
+            Cost::tiles(1)
+        };
+        return match self {
+            CostModel::Ignore => distance,
+            CostModel::Entry => distance * (1 + cost_calc(to)),
+            CostModel::Average => distance * (2 + cost_calc(from) + cost_calc(to)) / 2,
+            //How costs worked originally, diagonal steps cost the same and tile costs are multiplied by 5
+            CostModel::Legacy => distance * (1 + cost_calc(to) * 5),
+        };
+    }
+}
@@ -1,5 +1,18 @@
 use crate::data::Coord;
-use std::cmp::max;
+use crate::data::diagonal::Diagonal;
+use crate::data::cost_model::CostModel;
+use crate::data::cost::Cost;
+use crate::algos::{DIAGONAL_FACTOR, diagonal_step_cost};
+use std::cmp::{max, min};
+
+//Weighted Manhattan is Manhattan plus 1/TIE_BREAK_STEPS of itself, so it only overestimates
+//by more than one step on paths longer than this
+const TIE_BREAK_STEPS: i32 = 64;
+//Nudging towards the straight line takes up to 1/NUDGE_FRACTION of a tile off the estimate, all of it on the line
+//and less the further off it a node is, half as much once the cross product reaches NUDGE_HALF_CROSS
+//Taking it off rather than adding it keeps the estimate under the true cost however big the map is
+const NUDGE_FRACTION: i32 = 10;
+const NUDGE_HALF_CROSS: i32 = 16;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Heuristic {
@@ -7,9 +20,20 @@ pub enum Heuristic {
     Manhattan,
     Euclidean,
     Chebyshev,
+    Octile,
+    ManhattanWeighted,
+    OctileStraight,
     Mine
 }
 
+//How the distances to each end are combined when a variant has more than one
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Combination {
+    Min,
+    Max,
+    Sum,
+}
+
 trait Abs {
     fn abs(self) -> Self;
 }
@@ -27,12 +51,15 @@ impl Heuristic {
             Heuristic::Manhattan => String::from("Manhattan"),
             Heuristic::Euclidean => String::from("Euclidean"),
             Heuristic::Chebyshev => String::from("Chebyshec"),
+            Heuristic::Octile => String::from("Octile"),
+            Heuristic::ManhattanWeighted => String::from("Manhattan (weighted)"),
+            Heuristic::OctileStraight => String::from("Octile (straight line)"),
             Heuristic::Mine => String::from("Fast but less accurate"),
         };
     }
 
     pub fn len() -> usize {
-        5
+        8
     }
 
     pub fn from_index(idx: usize) -> Heuristic {
@@ -41,29 +68,108 @@ impl Heuristic {
             1 => Heuristic::Manhattan,
             2 => Heuristic::Euclidean,
             3 => Heuristic::Chebyshev,
-            4 => Heuristic::Mine,
+            4 => Heuristic::Octile,
+            5 => Heuristic::ManhattanWeighted,
+            6 => Heuristic::OctileStraight,
+            7 => Heuristic::Mine,
             _ => panic!("Invalid index: {}", idx),
         };
     }
 }
 
 
+impl Combination {
+    pub fn name(&self) -> String {
+        return match self {
+            Combination::Min => String::from("Nearest end (min)"),
+            Combination::Max => String::from("Furthest end (max)"),
+            Combination::Sum => String::from("All ends added (sum)"),
+        };
+    }
+
+    pub fn len() -> usize {
+        3
+    }
+
+    pub fn from_index(idx: usize) -> Combination {
+        return match idx {
+            0 => Combination::Min,
+            1 => Combination::Max,
+            2 => Combination::Sum,
+            _ => panic!("Invalid index: {}", idx),
+        };
+    }
+}
+
 impl Heuristic {
-    pub fn calc_multiple(&self, current: &Coord, ends: &Vec<Coord>) -> i32 {
-        return ends.iter().map(|end| self.calc_fixed(current, end)).sum();
+    //Whether the heuristic never overestimates the cost to an end, A* only promises the cheapest path if it doesn't
+    //Every step costs at least one tile so it only depends on how many steps are needed and how long diagonal steps are
+    //With several ends only taking the nearest one is safe, the others estimate the cost to ends that may not be the closest
+    pub fn is_admissible(&self, diagonal: Diagonal, cost_model: CostModel, combination: Combination, end_count: usize) -> bool {
+        if end_count > 1 && combination != Combination::Min {
+            return false;
+        }
+        return match self {
+            Heuristic::None => true,
+            Heuristic::Manhattan => diagonal == Diagonal::Never,
+            Heuristic::Euclidean => diagonal == Diagonal::Never || cost_model.diagonal_factor() >= DIAGONAL_FACTOR,
+            Heuristic::Chebyshev => true,
+            Heuristic::Octile => diagonal == Diagonal::Never || cost_model.diagonal_factor() >= DIAGONAL_FACTOR,
+            //Tie breaking makes the estimate slightly too big so A* can settle for a path a little more expensive
+            Heuristic::ManhattanWeighted => false,
+            Heuristic::OctileStraight => Heuristic::Octile.is_admissible(diagonal, cost_model, combination, end_count),
+            Heuristic::Mine => false,
+        };
     }
 
-    pub fn calc_fixed(&self, current: &Coord, end: &Coord) -> i32 {
-        return self.calc((current.x - end.x).abs(), (current.y - end.y).abs());
+    //The closest admissible estimate for how far a step across free tiles goes
+    pub fn suggested(diagonal: Diagonal, cost_model: CostModel) -> Heuristic {
+        if diagonal == Diagonal::Never {
+            return Heuristic::Manhattan;
+        }
+        if cost_model.diagonal_factor() >= DIAGONAL_FACTOR {
+            return Heuristic::Octile;
+        }
+        return Heuristic::Chebyshev;
+    }
+}
+
+impl Heuristic {
+    //`origin` is where the search started, only used to nudge towards the straight line to each end
+    pub fn calc_multiple(&self, origin: &Coord, current: &Coord, ends: &Vec<Coord>, combination: Combination) -> Cost {
+        let distances = ends.iter().map(|end| self.calc_fixed(origin, current, end));
+        return match combination {
+            Combination::Min => distances.min().unwrap_or(Cost::ZERO),
+            Combination::Max => distances.max().unwrap_or(Cost::ZERO),
+            Combination::Sum => distances.sum(),
+        };
+    }
+
+    pub fn calc_fixed(&self, origin: &Coord, current: &Coord, end: &Coord) -> Cost {
+        let distance = self.calc((current.x - end.x).abs(), (current.y - end.y).abs());
+        if self == &Heuristic::OctileStraight {
+            //Of nodes with the same f, prefers the ones closest to the line from the origin to the end
+            let cross = ((current.x - end.x) * (origin.y - end.y) - (origin.x - end.x) * (current.y - end.y)).abs();
+            let nudge = Cost::tiles(1) / NUDGE_FRACTION * NUDGE_HALF_CROSS / (cross + NUDGE_HALF_CROSS);
+            return max(Cost::ZERO, distance - nudge);
+        }
+        return distance;
     }
 
-    pub fn calc(&self, dx: i32, dy: i32) -> i32 {
+    pub fn calc(&self, dx: i32, dy: i32) -> Cost {
         match self {
-            Heuristic::None => return 0,
-            Heuristic::Manhattan => return dx + dy,
-            Heuristic::Euclidean => return ((dx * dx + dy * dy) as f32).sqrt() as i32,
-            Heuristic::Chebyshev => return max(dx, dy),
-            Heuristic::Mine => return dx.pow(2) + dy.pow(2)
+            Heuristic::None => return Cost::ZERO,
+            Heuristic::Manhattan => return Cost::tiles(dx + dy),
+            Heuristic::Euclidean => return Cost::floor(((dx * dx + dy * dy) as f64).sqrt()),
+            Heuristic::Chebyshev => return Cost::tiles(max(dx, dy)),
+            //Straight steps until level with the end then diagonal ones, each costing the same as moving across a free tile
+            Heuristic::Octile => return Cost::tiles(max(dx, dy) - min(dx, dy)) + diagonal_step_cost(DIAGONAL_FACTOR) * min(dx, dy),
+            Heuristic::ManhattanWeighted => {
+                let manhattan = Heuristic::Manhattan.calc(dx, dy);
+                return manhattan + manhattan / TIE_BREAK_STEPS;
+            }
+            Heuristic::OctileStraight => return Heuristic::Octile.calc(dx, dy),
+            Heuristic::Mine => return Cost::tiles(dx.pow(2) + dy.pow(2))
         }
     }
 }
\ No newline at end of file
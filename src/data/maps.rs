@@ -1,18 +1,27 @@
 use crate::data::Coord;
-use ggez::{Context, filesystem};
-use std::io::Read;
-use crate::{GRID_VERT_COUNT, GRID_HORZ_COUNT};
+use ggez::{Context, GameResult, filesystem};
+use std::io::{Read, Write};
+use std::fs;
+use crate::project_resources_dir;
 use std::str::Chars;
+use std::rc::Rc;
+use std::fmt::{Display, Formatter};
+use std::fmt;
+use std::cell::RefCell;
 
-#[allow(dead_code)]
 pub const NODE_FREE: i32 = 0;
 pub const NODE_WALL: i32 = -1;
 
+//Tile costs shared between the executor and the algorithm so they can be changed during a run
+pub type SharedCost = Rc<RefCell<Vec<Vec<i32>>>>;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Variant {
     pub start: Coord,
     pub ends: Vec<Coord>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Map {
     pub variants: Vec<Variant>,
     pub cost: Vec<Vec<i32>>,
@@ -26,6 +35,12 @@ impl Map {
     pub fn get_row_count(&self) -> usize {
         self.cost[0].len()
     }
+
+    pub fn is_uniform_cost(&self) -> bool {
+        self.cost.iter()
+            .flatten()
+            .all(|&cost| cost == NODE_FREE || cost == NODE_WALL)
+    }
 }
 
 #[inline]
@@ -33,106 +48,210 @@ pub fn node_cost_to_percentage(cost: i32) -> f32 {
     cost as f32 / 10.
 }
 
-pub fn read_map_file(ctx: &mut Context, which: usize) -> Map {
-    if let Ok(mut file) = filesystem::open(ctx, format!("/map{}", which)) {
-        let mut buffer = String::new();
-        match file.read_to_string(&mut buffer) {
-            Ok(_) => {
-                let lines: Vec<&str> = buffer.split_whitespace().collect();
-
-                let mut cost = vec![vec![0; GRID_VERT_COUNT]; GRID_HORZ_COUNT];
-
-                let mut x = 0_usize;
-                let mut y = 0_usize;
-
-                let mut variants = vec![];
-
-                lines.iter().for_each(|&line| {
-                    let mut chars = line.chars();
-                    match chars.next().unwrap() {
-                        'M' => {
-                            for letter in chars {
-                                match letter {
-                                    '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' => cost[x][y] = letter.to_digit(10).unwrap() as i32,
-                                    '9' => cost[x][y] = NODE_WALL,
-                                    '0' | '\n' => { /* ignored */ }
-                                    _ => panic!("Unexpected character {} found at {},{} in map {}", letter, x, y, which)
-                                }
-                                if letter != '\n' {
-                                    x += 1;
-                                }
-                                if x >= GRID_HORZ_COUNT {
-                                    x = 0;
-                                    y += 1;
-                                }
-                                if y > GRID_VERT_COUNT {
-                                    break;
-                                }
-                            }
-                        }
-                        'S' => {
-                            variants.push(Variant { start: convert_chars_to_coords(chars), ends: vec![] });
-                        }
-                        'E' => {
-                            if let Some(variant) = variants.last_mut() {
-                                variant.ends.push(convert_chars_to_coords(chars));
-                            } else {
-                                panic!("End without start in map {}", which);
-                            }
-                        }
-                        _ => {}
-                    }
-                });
+//Why a map file couldn't be loaded
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapErrorKind {
+    Missing,
+    Unreadable(String),
+    UnexpectedCharacter(char),
+    InvalidCoord(String),
+    EndWithoutStart,
+    NoTiles,
+    RowLength { expected: usize, found: usize },
+    NoVariants,
+    NoEnds,
+    OutOfBounds(Coord),
+    StartInWall(Coord),
+    EndInWall(Coord),
+}
 
-                if y < GRID_VERT_COUNT {
-                    panic!("map {} is too short", which);
-                }
+impl Display for MapErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            MapErrorKind::Missing => write!(f, "file missing"),
+            MapErrorKind::Unreadable(err) => write!(f, "couldn't be read: {}", err),
+            MapErrorKind::UnexpectedCharacter(letter) => write!(f, "unexpected character '{}'", letter),
+            MapErrorKind::InvalidCoord(text) => write!(f, "'{}' isn't a coord, expected x,y", text),
+            MapErrorKind::EndWithoutStart => write!(f, "end before any start"),
+            MapErrorKind::NoTiles => write!(f, "no rows of tiles"),
+            MapErrorKind::RowLength { expected, found } => write!(f, "row has {} tiles but the first row has {}", found, expected),
+            MapErrorKind::NoVariants => write!(f, "no starts"),
+            MapErrorKind::NoEnds => write!(f, "start has no ends"),
+            MapErrorKind::OutOfBounds(xy) => write!(f, "{} is outside the map", xy),
+            MapErrorKind::StartInWall(xy) => write!(f, "start {} is in a wall", xy),
+            MapErrorKind::EndInWall(xy) => write!(f, "end {} is in a wall", xy),
+        }
+    }
+}
 
-                if variants.is_empty() {
-                    panic!("map {} has no variants", which);
-                }
+//Where and why a map file couldn't be loaded, line and column start at 1 and are 0 when the whole file is at fault
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapError {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub kind: MapErrorKind,
+}
+
+impl Display for MapError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.line == 0 {
+            return write!(f, "{}: {}", self.file, self.kind);
+        }
+        return write!(f, "{}:{}:{}: {}", self.file, self.line, self.column, self.kind);
+    }
+}
+
+pub fn map_file_name(which: usize) -> String {
+    format!("map{}", which)
+}
+
+pub fn read_map_file(ctx: &mut Context, which: usize) -> Result<Map, MapError> {
+    let file_name = map_file_name(which);
+    let error = |kind: MapErrorKind| MapError { file: file_name.clone(), line: 0, column: 0, kind };
+    let mut file = filesystem::open(ctx, format!("/{}", file_name)).map_err(|_| error(MapErrorKind::Missing))?;
+    let mut buffer = String::new();
+    file.read_to_string(&mut buffer).map_err(|err| error(MapErrorKind::Unreadable(err.to_string())))?;
+    return parse_map(&buffer, &file_name);
+}
+
+//A line number and the column of the line's letter
+type LinePosition = (usize, usize);
 
-                variants.iter().for_each(|variant| {
-                    if variant.ends.is_empty() {
-                        panic!("variant in {} has no end", which);
-                    } else {
-                        variant.ends.iter().for_each(|end| {
-                            if cost[end.x as usize][end.y as usize] == NODE_WALL {
-                                panic!("map {} has end in a wall at {},{}", which, end.x, end.y)
-                            }
-                        });
+//Reads the text of a map file, `file` is only used in errors
+//The map is as wide as the first M line and has one row per M line
+pub fn parse_map(text: &str, file: &str) -> Result<Map, MapError> {
+    let error = |line: usize, column: usize, kind: MapErrorKind| MapError { file: file.to_string(), line, column, kind };
+
+    let mut rows: Vec<Vec<i32>> = vec![];
+
+    //each variant with the line and column its start was declared at and those of its ends
+    let mut variants: Vec<(Variant, LinePosition, Vec<LinePosition>)> = vec![];
+
+    for (line_idx, line) in text.lines().enumerate() {
+        let line_number = line_idx + 1;
+        //columns are counted from the line's letter, whatever it's indented by
+        let letter_column = line.len() - line.trim_start().len() + 1;
+        let mut chars = line.trim().chars();
+        match chars.next() {
+            Some('M') => {
+                let mut row = vec![];
+                for (char_idx, letter) in chars.enumerate() {
+                    let column = letter_column + 1 + char_idx;
+                    match letter {
+                        '0'..='8' => row.push(letter.to_digit(10).unwrap() as i32),
+                        '9' => row.push(NODE_WALL),
+                        _ => return Err(error(line_number, column, MapErrorKind::UnexpectedCharacter(letter)))
                     }
-                    if variant.start.x as usize > GRID_HORZ_COUNT || variant.start.y as usize > GRID_HORZ_COUNT {
-                        panic!("variant in {} has start outside bounds", which);
-                    } else {
-                        if cost[variant.start.x as usize][variant.start.y as usize] == NODE_WALL {
-                            panic!("map {} has start in a wall at {},{}", which, variant.start.x, variant.start.y)
-                        }
+                }
+                if let Some(first) = rows.first() {
+                    if row.len() != first.len() {
+                        let column = letter_column + 1 + row.len().min(first.len());
+                        return Err(error(line_number, column, MapErrorKind::RowLength { expected: first.len(), found: row.len() }));
                     }
-                });
-
-                return Map {
-                    variants,
-                    cost,
-                };
+                }
+                rows.push(row);
             }
-            Err(err) => {
-                eprintln!("{}", err);
-                panic!("Failed to read map {}", which);
+            Some('S') => {
+                let start = convert_chars_to_coords(chars).map_err(|kind| error(line_number, letter_column + 1, kind))?;
+                variants.push((Variant { start, ends: vec![] }, (line_number, letter_column), vec![]));
             }
+            Some('E') => {
+                let end = convert_chars_to_coords(chars).map_err(|kind| error(line_number, letter_column + 1, kind))?;
+                match variants.last_mut() {
+                    Some((variant, _, end_positions)) => {
+                        variant.ends.push(end);
+                        end_positions.push((line_number, letter_column));
+                    }
+                    None => return Err(error(line_number, letter_column, MapErrorKind::EndWithoutStart)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if rows.is_empty() || rows[0].is_empty() {
+        return Err(error(0, 0, MapErrorKind::NoTiles));
+    }
+    let width = rows[0].len();
+    let height = rows.len();
+    let cost: Vec<Vec<i32>> = (0..width)
+        .map(|x| rows.iter().map(|row| row[x]).collect())
+        .collect();
+
+    if variants.is_empty() {
+        return Err(error(0, 0, MapErrorKind::NoVariants));
+    }
+
+    //coords are reported at the column they start at, just after the letter
+    let check_tile = |xy: Coord, (line, letter_column): LinePosition, in_wall: MapErrorKind| {
+        if xy.is_out_of_bounds(width as i32, height as i32) {
+            return Err(error(line, letter_column + 1, MapErrorKind::OutOfBounds(xy)));
+        }
+        if cost[xy.x as usize][xy.y as usize] == NODE_WALL {
+            return Err(error(line, letter_column + 1, in_wall));
+        }
+        return Ok(());
+    };
+    for (variant, (line, letter_column), end_positions) in &variants {
+        if variant.ends.is_empty() {
+            return Err(error(*line, *letter_column, MapErrorKind::NoEnds));
+        }
+        check_tile(variant.start, (*line, *letter_column), MapErrorKind::StartInWall(variant.start))?;
+        for (end, end_position) in variant.ends.iter().zip(end_positions) {
+            check_tile(*end, *end_position, MapErrorKind::EndInWall(*end))?;
         }
+    }
+
+    return Ok(Map {
+        variants: variants.into_iter().map(|(variant, _, _)| variant).collect(),
+        cost,
+    });
+}
+
+//Writes a map in the format parse_map reads: a line of costs per row, walls as 9, then each variant's start and ends
+pub fn write_map(map: &Map) -> String {
+    let mut text = String::new();
+    for y in 0..map.get_row_count() {
+        text.push('M');
+        for x in 0..map.get_column_count() {
+            match map.cost[x][y] {
+                NODE_WALL => text.push('9'),
+                cost => text.push_str(&cost.to_string()),
+            }
+        }
+        text.push('\n');
+    }
+    for variant in &map.variants {
+        text.push_str(&format!("S{},{}\n", variant.start.x, variant.start.y));
+        for end in &variant.ends {
+            text.push_str(&format!("E{},{}\n", end.x, end.y));
+        }
+    }
+    return text;
+}
+
+//Replaces the map file in the project's resources, or in the user's config folder when not run through cargo
+pub fn write_map_file(ctx: &mut Context, which: usize, text: &str) -> GameResult<()> {
+    let name = map_file_name(which);
+    if let Some(mut path) = project_resources_dir() {
+        path.push(name);
+        fs::write(path, text)?;
     } else {
-        panic!("Map {} missing", which);
+        let mut file = filesystem::create(ctx, format!("/{}", name))?;
+        file.write_all(text.as_bytes())?;
     }
+    Ok(())
 }
 
-fn convert_chars_to_coords(chars: Chars) -> Coord {
-    let char_array = chars.collect::<Vec<char>>();
-    let text = char_array.iter().collect::<String>();
-    let mut split = text.split(",");
-    let x = split.next();
-    let y = split.next();
-    let x = x.expect("Invalid coord").parse().expect("Coord not a num");
-    let y = y.expect("Invalid coord").parse().expect("Coord not a num");
-    return Coord::new(x, y);
-}
\ No newline at end of file
+fn convert_chars_to_coords(chars: Chars) -> Result<Coord, MapErrorKind> {
+    let text = chars.collect::<String>();
+    let invalid = || MapErrorKind::InvalidCoord(text.clone());
+    let mut split = text.split(',');
+    let x = split.next().and_then(|x| x.trim().parse().ok()).ok_or_else(invalid)?;
+    let y = split.next().and_then(|y| y.trim().parse().ok()).ok_or_else(invalid)?;
+    if split.next().is_some() {
+        return Err(invalid());
+    }
+    return Ok(Coord::new(x, y));
+}
@@ -1,12 +1,18 @@
+pub mod cost;
+pub mod cost_model;
 pub mod diagonal;
 pub mod heuristic;
 pub mod maps;
 
+#[cfg(test)]
+mod tests;
+
 use std::ops::Add;
 use std::ops::Sub;
 use std::fmt::{Display, Formatter};
 use std::fmt;
 use std::rc::Rc;
+use std::hash::{Hash, Hasher};
 
 #[derive(Debug, Default, Eq, Clone, Copy)]
 pub struct Coord {
@@ -34,6 +40,13 @@ impl PartialEq for Coord {
     }
 }
 
+impl Hash for Coord {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.x.hash(state);
+        self.y.hash(state);
+    }
+}
+
 impl PartialEq<Rc<Coord>> for Coord {
     fn eq(&self, other: &Rc<Coord>) -> bool {
         self.x == other.x && self.y == other.y
@@ -87,43 +100,3 @@ impl Sub for Coord {
         Coord::new(self.x - rhs.x, self.y - rhs.y)
     }
 }
-
-#[derive(Debug, Eq, Default, Clone)]
-pub struct Node {
-    pub xy: Coord,
-    //distance to start node
-    pub g: i32,
-    //heuristic — estimated distance to end node
-    pub h: i32,
-    //node cost
-    pub f: i32,
-    pub parent: Option<Rc<Node>>,
-}
-
-impl PartialEq for Node {
-    fn eq(&self, other: &Node) -> bool {
-        self.xy == other.xy
-    }
-}
-
-impl PartialEq<Rc<Node>> for Node {
-    fn eq(&self, other: &Rc<Node>) -> bool {
-        self.xy == other.xy
-    }
-}
-
-impl Node {
-    pub fn new(xy: Coord, parent: Option<Rc<Node>>) -> Node {
-        return Node {
-            xy,
-            parent,
-            ..Node::default()
-        };
-    }
-}
-
-impl From<Coord> for Node {
-    fn from(coord: Coord) -> Self {
-        Node::new(coord, None)
-    }
-}
\ No newline at end of file
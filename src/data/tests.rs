@@ -0,0 +1,85 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::data::Coord;
+use crate::data::maps::{parse_map, write_map, map_file_name, MapErrorKind, NODE_WALL};
+
+//Every map shipped in resources, with the number it's loaded as
+fn resource_maps() -> Vec<(usize, String)> {
+    let mut dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    dir.push("resources");
+    let mut maps = vec![];
+    for entry in fs::read_dir(dir).expect("resources folder missing") {
+        let path = entry.expect("Unreadable resources entry").path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        if let Some(which) = name.strip_prefix("map").and_then(|which| which.parse().ok()) {
+            maps.push((which, fs::read_to_string(&path).expect("Unreadable map file")));
+        }
+    }
+    maps.sort_by_key(|(which, _)| *which);
+    return maps;
+}
+
+#[test]
+fn written_maps_parse_back_the_same() {
+    let maps = resource_maps();
+    assert!(!maps.is_empty(), "no maps found in resources");
+    for (which, text) in maps {
+        let file = map_file_name(which);
+        let map = parse_map(&text, &file).unwrap_or_else(|err| panic!("{}", err));
+        let written = write_map(&map);
+        let reparsed = parse_map(&written, &file).unwrap_or_else(|err| panic!("written {}", err));
+        assert_eq!(reparsed, map, "map {} changed after being written", which);
+        assert_eq!(write_map(&reparsed), written, "map {} was written differently the second time", which);
+    }
+}
+
+#[test]
+fn broken_maps_report_where_they_went_wrong() {
+    let (_, text) = resource_maps().remove(0);
+    let mut lines: Vec<String> = text.lines().map(String::from).collect();
+    lines[2].replace_range(5..6, "x");
+    let err = parse_map(&lines.join("\n"), "map0").unwrap_err();
+    assert_eq!((err.line, err.column, err.kind), (3, 6, MapErrorKind::UnexpectedCharacter('x')));
+
+    let mut ragged: Vec<String> = text.lines().map(String::from).collect();
+    ragged[4].pop();
+    let err = parse_map(&ragged.join("\n"), "map0").unwrap_err();
+    assert_eq!((err.line, err.kind), (5, MapErrorKind::RowLength { expected: 32, found: 31 }));
+
+    let mut walled: Vec<String> = text.lines().map(String::from).collect();
+    walled.push(String::from("S1,1"));
+    walled.push(String::from("E0,0"));
+    let err = parse_map(&walled.join("\n"), "map0").unwrap_err();
+    assert_eq!((err.line, err.kind), (walled.len(), MapErrorKind::EndInWall(Coord::new(0, 0))));
+}
+
+#[test]
+fn maps_take_their_size_from_their_rows() {
+    let text = "M0000\nM0900\nM0010\nS0,0\nE3,2\n";
+    let map = parse_map(text, "small").unwrap_or_else(|err| panic!("{}", err));
+    assert_eq!((map.get_column_count(), map.get_row_count()), (4, 3));
+    assert_eq!(map.cost[1][1], NODE_WALL);
+    assert_eq!(map.cost[2][2], 1);
+    assert_eq!(write_map(&map), text);
+
+    let err = parse_map("M0000\nM00\nS0,0\nE3,0\n", "ragged").unwrap_err();
+    assert_eq!(err.kind, MapErrorKind::RowLength { expected: 4, found: 2 });
+    let err = parse_map("M0000\nM0000\nS0,0\nE3,2\n", "small").unwrap_err();
+    assert_eq!((err.line, err.kind), (4, MapErrorKind::OutOfBounds(Coord::new(3, 2))));
+}
+
+#[test]
+fn indented_lines_report_columns_from_their_letter() {
+    let check = |text: &str, expected: (usize, usize, MapErrorKind)| {
+        let err = parse_map(text, "indented").unwrap_err();
+        assert_eq!((err.line, err.column, err.kind), expected, "{}", text);
+    };
+    check("  M00x0\nS0,0\nE3,0\n", (1, 6, MapErrorKind::UnexpectedCharacter('x')));
+    check("M0000\n  M000\nS0,0\nE3,0\n", (2, 7, MapErrorKind::RowLength { expected: 4, found: 3 }));
+    check("M0000\n  S0;0\nE3,0\n", (2, 4, MapErrorKind::InvalidCoord(String::from("0;0"))));
+    check("M0000\n  E3,0\nS0,0\n", (2, 3, MapErrorKind::EndWithoutStart));
+    check("M0000\n  S0,0\n", (2, 3, MapErrorKind::NoEnds));
+    check("M0000\nS0,0\n  E4,0\n", (3, 4, MapErrorKind::OutOfBounds(Coord::new(4, 0))));
+    check("M9000\n  S0,0\nE3,0\n", (2, 4, MapErrorKind::StartInWall(Coord::new(0, 0))));
+    check("M0009\nS0,0\n\tE3,0\n", (3, 3, MapErrorKind::EndInWall(Coord::new(3, 0))));
+}
@@ -1,8 +1,10 @@
 use ggez::{Context, GameResult};
-use crate::point;
+use ggez::graphics::{Text, TextFragment, Scale, Align};
+use crate::{point, DPPoint};
 use crate::graphics::renderer::Renderer;
 use crate::data::Coord;
-use crate::data::maps::{Map, node_cost_to_percentage};
+use crate::data::maps::{Map, MapError, node_cost_to_percentage};
+use crate::algos::{Frontier, Path};
 
 //PUBLIC
 
@@ -25,30 +27,69 @@ pub fn draw_map_with_costs(ctx: &mut Context, renderer: &mut Renderer, map_offse
     Ok(())
 }
 
-pub fn draw_map_with_costs_nodes(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), cell_size: f32, map: &Map, open_nodes: &Vec<Coord>, closed_nodes: &Vec<Coord>, variant: usize) -> GameResult<()> {
+//Largest cell size that fits every tile of `map` into `area` pixels
+pub fn fit_cell_size(map: &Map, area: (f32, f32)) -> f32 {
+    let cols = map.get_column_count() as f32;
+    let rows = map.get_row_count() as f32;
+    return (area.0 / cols).min(area.1 / rows);
+}
+
+//Drawn in place of a map that couldn't be loaded
+pub fn draw_map_error(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), size: (f32, f32), error: &MapError) -> GameResult<()> {
+    let outline = renderer.make_rect_mesh(ctx, size.0, size.1, false, 2.)?;
+    renderer.draw_coloured_mesh(ctx, outline.as_ref(), point(map_offset.0, map_offset.1), (1., 0.3, 0.3, 1.).into());
+    let mut text = Text::new(TextFragment {
+        text: error.to_string(),
+        color: Some((1., 0.5, 0.5, 1.).into()),
+        scale: Some(Scale::uniform(renderer.calc_height(0.025))),
+        ..TextFragment::default()
+    });
+    text.set_bounds(point(size.0 - 16., size.1 - 16.), Align::Left);
+    renderer.draw_mesh(ctx, &text, point(map_offset.0 + 8., map_offset.1 + 8.));
+
+    Ok(())
+}
+
+pub fn draw_map_with_costs_nodes(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), cell_size: f32, map: &Map, frontiers: &Vec<Frontier>, variant: usize) -> GameResult<()> {
     let cols = map.get_column_count();
     let rows = map.get_row_count();
     draw_map_grid(ctx, renderer, map_offset, cell_size, cols, rows)?;
     draw_map_costs(ctx, renderer, map_offset, cell_size, cols, rows, map)?;
-    draw_map_nodes(ctx, renderer, map_offset, cell_size, open_nodes, closed_nodes)?;
+    draw_map_nodes(ctx, renderer, map_offset, cell_size, frontiers, true)?;
     draw_map_start_end(ctx, renderer, map_offset, cell_size, map.variants[variant].start, &map.variants[variant].ends)?;
 //    draw_debug_node_numbers(ctx, renderer, map_offset, cell_size, cols, rows)?;
 
     Ok(())
 }
 
-pub fn draw_map_with_costs_path(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), cell_size: f32, map: &Map, path: &Vec<Coord>, closed_nodes: &Vec<Coord>, variant: usize) -> GameResult<()> {
+pub fn draw_map_with_costs_path(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), cell_size: f32, map: &Map, path: &Path, frontiers: &Vec<Frontier>, variant: usize) -> GameResult<()> {
     let cols = map.get_column_count();
     let rows = map.get_row_count();
     draw_map_grid(ctx, renderer, map_offset, cell_size, cols, rows)?;
     draw_map_costs(ctx, renderer, map_offset, cell_size, cols, rows, map)?;
-    draw_map_nodes(ctx, renderer, map_offset, cell_size, &vec![], closed_nodes)?;
+    draw_map_nodes(ctx, renderer, map_offset, cell_size, frontiers, false)?;
     draw_map_path(ctx, renderer, map_offset, cell_size, path)?;
     draw_map_start_end(ctx, renderer, map_offset, cell_size, map.variants[variant].start, &map.variants[variant].ends)?;
 
     Ok(())
 }
 
+pub fn draw_map_jump_points(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), cell_size: f32, jump_points: &Vec<Coord>) -> GameResult<()> {
+    let marker_mesh = renderer.make_square_mesh(ctx, cell_size * 0.5, true, 2.)?;
+    let jump_color = (1.0, 0.6, 0.1, 0.9).into();
+    for point_xy in jump_points {
+        renderer.draw_coloured_mesh(ctx, marker_mesh.as_ref(), point(map_offset.0 + (point_xy.x as f32 * cell_size) + (cell_size * 0.25), map_offset.1 + (point_xy.y as f32 * cell_size) + (cell_size * 0.25)), jump_color);
+    }
+    Ok(())
+}
+
+pub fn draw_map_legs(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), cell_size: f32, legs: &Vec<Path>) -> GameResult<()> {
+    for leg in legs {
+        draw_map_path(ctx, renderer, map_offset, cell_size, leg)?;
+    }
+    Ok(())
+}
+
 fn draw_map_grid(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), cell_size: f32, cols: usize, rows: usize) -> GameResult<()> {
     let grid_mesh = renderer.make_grid_mesh(ctx, cell_size, cols, rows, 160)?;
     renderer.draw_mesh(ctx, grid_mesh.as_ref(), point(map_offset.0, map_offset.1));
@@ -73,16 +114,24 @@ fn draw_map_costs(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32,
     Ok(())
 }
 
-fn draw_map_nodes(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), cell_size: f32, open_nodes: &Vec<Coord>, closed_nodes: &Vec<Coord>) -> GameResult<()> {
+//Colours for each frontier, repeats if there are more frontiers than colours
+const OPEN_COLORS: [(f32, f32, f32, f32); 2] = [(0.5, 0.5, 0.7, 0.8), (0.7, 0.55, 0.4, 0.8)];
+const CLOSED_COLORS: [(f32, f32, f32, f32); 2] = [(0.3, 0.3, 0.5, 0.8), (0.5, 0.35, 0.25, 0.8)];
+
+fn draw_map_nodes(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), cell_size: f32, frontiers: &Vec<Frontier>, include_open: bool) -> GameResult<()> {
     let square_mesh = renderer.make_square_mesh(ctx, cell_size, true, 2.)?;
-    let open_color = (0.5, 0.5, 0.7, 0.8).into();
-    let closed_color = (0.3, 0.3, 0.5, 0.8).into();
 
-    for open in open_nodes {
-        renderer.draw_coloured_mesh(ctx, square_mesh.as_ref(), point(map_offset.0 + (open.x as f32 * cell_size), map_offset.1 + (open.y as f32 * cell_size)), open_color);
-    }
-    for closed in closed_nodes {
-        renderer.draw_coloured_mesh(ctx, square_mesh.as_ref(), point(map_offset.0 + (closed.x as f32 * cell_size), map_offset.1 + (closed.y as f32 * cell_size)), closed_color);
+    for (idx, frontier) in frontiers.iter().enumerate() {
+        let open_color = OPEN_COLORS[idx % OPEN_COLORS.len()];
+        let closed_color = CLOSED_COLORS[idx % CLOSED_COLORS.len()];
+        if include_open {
+            for open in &frontier.open {
+                renderer.draw_coloured_mesh(ctx, square_mesh.as_ref(), point(map_offset.0 + (open.x as f32 * cell_size), map_offset.1 + (open.y as f32 * cell_size)), open_color.into());
+            }
+        }
+        for closed in &frontier.closed {
+            renderer.draw_coloured_mesh(ctx, square_mesh.as_ref(), point(map_offset.0 + (closed.x as f32 * cell_size), map_offset.1 + (closed.y as f32 * cell_size)), closed_color.into());
+        }
     }
     Ok(())
 }
@@ -103,11 +152,25 @@ fn draw_map_start_end(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f
     Ok(())
 }
 
-fn draw_map_path(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), cell_size: f32, path: &Vec<Coord>) -> GameResult<()> {
-    let square_mesh = renderer.make_square_mesh(ctx, cell_size, true, 2.)?;
+fn draw_map_path(ctx: &mut Context, renderer: &mut Renderer, map_offset: (f32, f32), cell_size: f32, path: &Path) -> GameResult<()> {
     let path_color = (0.5, 1.0, 0.5, 0.9).into();
-    for step in path {
-        renderer.draw_coloured_mesh(ctx, square_mesh.as_ref(), point(map_offset.0 + (step.x as f32 * cell_size), map_offset.1 + (step.y as f32 * cell_size)), path_color);
+    match path {
+        Path::Tiles(tiles) => {
+            let square_mesh = renderer.make_square_mesh(ctx, cell_size, true, 2.)?;
+            for step in tiles {
+                renderer.draw_coloured_mesh(ctx, square_mesh.as_ref(), point(map_offset.0 + (step.x as f32 * cell_size), map_offset.1 + (step.y as f32 * cell_size)), path_color);
+            }
+        }
+        Path::Waypoints(waypoints) => {
+            if waypoints.len() < 2 {
+                return Ok(());
+            }
+            let points: Vec<DPPoint> = waypoints.iter()
+                .map(|waypoint| point(map_offset.0 + ((waypoint.x as f32 + 0.5) * cell_size), map_offset.1 + ((waypoint.y as f32 + 0.5) * cell_size)))
+                .collect();
+            let line_mesh = renderer.make_line_mesh(ctx, &points, cell_size * 0.2, path_color)?;
+            renderer.draw_mesh(ctx, &line_mesh, point(0., 0.));
+        }
     }
     Ok(())
 }
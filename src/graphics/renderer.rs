@@ -70,7 +70,7 @@ impl Renderer {
     }
 
     pub fn make_rect_mesh(&mut self, ctx: &mut Context, width: f32, height: f32, filled: bool, thickness: f32) -> GameResult<Rc<Mesh>> {
-        let key = format!("rect_{}_{}_{}", width, height, filled);
+        let key = format!("rect_{}_{}_{}_{}", width, height, filled, thickness.to_bits());
         if self.mesh_cache.contains_key(&key) {
             return Ok(self.mesh_cache[&key].clone());
         } else {
@@ -129,6 +129,13 @@ impl Renderer {
         }
     }
 
+    //Not cached as the points are rarely the same twice
+    pub fn make_line_mesh(&mut self, ctx: &mut Context, points: &[DPPoint], width: f32, color: Color) -> GameResult<Mesh> {
+        let mut mesh_builder = MeshBuilder::new();
+        mesh_builder.line(points, width, color)?;
+        return mesh_builder.build(ctx);
+    }
+
     pub fn make_square_mesh(&mut self, ctx: &mut Context, cell_size: f32, filled: bool, thickness: f32) -> GameResult<Rc<Mesh>> {
         return self.make_rect_mesh(ctx, cell_size, cell_size, filled, thickness);
     }
@@ -8,7 +8,7 @@ mod std_ext;
 
 use ggez::{Context, ContextBuilder, GameResult, timer};
 use ggez::graphics as ggez_g;
-use ggez::event::{self, EventHandler, KeyMods, KeyCode};
+use ggez::event::{self, EventHandler, KeyMods, KeyCode, MouseButton};
 use ggez::conf::{WindowMode, WindowSetup};
 use ggez::mint::Point2;
 use ggez::graphics::{Text, Color, TextFragment, Scale};
@@ -20,8 +20,10 @@ use crate::graphics::renderer::Renderer;
 use crate::scenes::diagonal_picker::DiagonalPicker;
 use crate::scenes::map_picker::MapPicker;
 use crate::scenes::algo_picker::AlgoPicker;
+use crate::scenes::cost_model_picker::CostModelPicker;
 use crate::scenes::executor::Executor;
 use crate::scenes::heuristic_picker::HeuristicPicker;
+use crate::scenes::map_editor::MapEditor;
 use crate::scenes::{Scene, SceneParams};
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -29,8 +31,6 @@ use std::collections::HashMap;
 //This should be a wide screen (16:9) resolution (1920x1080, 1366x768, 1280x720, etc)
 pub const SCREEN_WIDTH: f32 = 1366.;
 pub const SCREEN_HEIGHT: f32 = 768.;
-pub const GRID_VERT_COUNT: usize = 17;
-pub const GRID_HORZ_COUNT: usize = 32;
 
 pub type DPPoint = Point2<f32>;
 
@@ -51,13 +51,9 @@ fn main() {
             ..WindowSetup::default()
         });
 
-    if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
-        let mut path = path::PathBuf::from(manifest_dir);
-        path.push("resources");
+    if let Some(path) = project_resources_dir() {
 //        println!("Adding path {:?} from manifest", path);
         cb = cb.add_resource_path(path);
-    } else {
-        path::PathBuf::from("./resources");
     }
 
     let (ctx, event_loop) = &mut cb
@@ -78,6 +74,14 @@ fn main() {
     }
 }
 
+//The project's resources folder when run through cargo, maps are read from and saved to it
+pub fn project_resources_dir() -> Option<path::PathBuf> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").ok()?;
+    let mut path = path::PathBuf::from(manifest_dir);
+    path.push("resources");
+    return Some(path);
+}
+
 struct GraphicalPath {
     active_scene: Option<Box<RefCell<dyn Scene>>>,
     renderer: Rc<RefCell<Renderer>>,
@@ -102,6 +106,10 @@ impl EventHandler for GraphicalPath {
             if scene.borrow_mut().is_complete() {
                 let params = scene.borrow_mut().get_next_stage_params(&mut self.cursor_mem);
                 match params {
+                    SceneParams::MapSelection => self.open_map_picker(ctx),
+                    SceneParams::MapEditing { map, which } => {
+                        self.active_scene = Some(Box::new(RefCell::new(MapEditor::new(map, which, &self.cursor_mem))));
+                    }
                     SceneParams::DiagonalSelection { map, algo, variant } => {
                         self.active_scene = Some(Box::new(RefCell::new(DiagonalPicker::new(map, algo, variant, &self.cursor_mem))));
                     }
@@ -109,12 +117,21 @@ impl EventHandler for GraphicalPath {
                         let picker = AlgoPicker::new(map.clone(), variant, &self.cursor_mem);
                         self.active_scene = Some(Box::new(RefCell::new(picker)));
                     }
-                    SceneParams::HeuristicSelection { map, algo, diagonal, variant } => {
-                        let picker = HeuristicPicker::new(map, algo, diagonal, variant, &self.cursor_mem);
+                    SceneParams::CostModelSelection { map, algo, diagonal, variant } => {
+                        let picker = CostModelPicker::new(map, algo, diagonal, variant, &self.cursor_mem);
+                        self.active_scene = Some(Box::new(RefCell::new(picker)));
+                    }
+                    SceneParams::HeuristicSelection { map, algo, diagonal, cost_model, variant } => {
+                        let picker = HeuristicPicker::new(map, algo, diagonal, cost_model, variant, &self.cursor_mem);
                         self.active_scene = Some(Box::new(RefCell::new(picker)));
                     }
-                    SceneParams::AlgoRunner { map, algo, algo_name, diagonal, heuristic, variant } => {
-                        let executor = Executor::new(map.clone(), algo, algo_name, diagonal.name(), heuristic.name(), variant, &self.cursor_mem);
+                    SceneParams::AlgoRunner { map, costs, algo, algo_name, diagonal, cost_model, heuristic, combination, variant } => {
+                        let heuristic_mode = if map.variants[variant].ends.len() > 1 {
+                            format!("{}, {}", heuristic.name(), combination.name())
+                        } else {
+                            heuristic.name()
+                        };
+                        let executor = Executor::new(map.clone(), costs, algo, algo_name, diagonal.name(), cost_model, heuristic_mode, variant, &self.cursor_mem);
                         self.active_scene = Some(Box::new(RefCell::new(executor)));
                     }
                     SceneParams::EndOfProgram => {
@@ -142,15 +159,14 @@ impl EventHandler for GraphicalPath {
     }
 
     fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, _keymod: KeyMods, _repeat: bool) {
+        if let Some(scene) = &mut self.active_scene {
+            if scene.borrow_mut().intercepts_key(keycode) {
+                return;
+            }
+        }
         match keycode {
             KeyCode::Escape | KeyCode::Q => ggez::event::quit(ctx),
-            KeyCode::R => {
-                let mut picker = MapPicker::new(&self.cursor_mem);
-                if picker.setup(ctx, &mut self.renderer.borrow_mut()).is_err() {
-                    panic!("Failed to setup map picked");
-                }
-                self.active_scene = Some(Box::new(RefCell::new(picker)));
-            }
+            KeyCode::R => self.open_map_picker(ctx),
             _ => {
                 if let Some(scene) = &mut self.active_scene {
                     scene.borrow_mut().on_button_down(keycode);
@@ -159,6 +175,30 @@ impl EventHandler for GraphicalPath {
         }
     }
 
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        if let Some(scene) = &mut self.active_scene {
+            scene.borrow_mut().on_mouse_move(point(x, y));
+        }
+    }
+
+    fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if let Some(scene) = &mut self.active_scene {
+            scene.borrow_mut().on_mouse_down(point(x, y), button);
+        }
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if let Some(scene) = &mut self.active_scene {
+            scene.borrow_mut().on_mouse_click(point(x, y), button);
+        }
+    }
+
+    fn mouse_wheel_event(&mut self, _ctx: &mut Context, _x: f32, y: f32) {
+        if let Some(scene) = &mut self.active_scene {
+            scene.borrow_mut().on_mouse_scroll(y);
+        }
+    }
+
     fn key_up_event(&mut self, _ctx: &mut Context, keycode: KeyCode, _keymod: KeyMods) {
         match keycode {
             _ => {
@@ -171,6 +211,14 @@ impl EventHandler for GraphicalPath {
 }
 
 impl GraphicalPath {
+    fn open_map_picker(&mut self, ctx: &mut Context) {
+        let mut picker = MapPicker::new(&self.cursor_mem);
+        if picker.setup(ctx, &mut self.renderer.borrow_mut()).is_err() {
+            panic!("Failed to setup map picked");
+        }
+        self.active_scene = Some(Box::new(RefCell::new(picker)));
+    }
+
     fn draw_fps(&mut self, ctx: &mut Context) {
         let text = Text::new(TextFragment {
             text: format!("{:.0}", timer::fps(ctx)),
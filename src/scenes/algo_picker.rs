@@ -1,7 +1,7 @@
-use crate::{point};
-use crate::scenes::{Scene, SceneParams};
+use crate::{point, DPPoint};
+use crate::scenes::{Scene, SceneParams, ListLayout};
 use ggez::{Context, GameError};
-use ggez::event::KeyCode;
+use ggez::event::{KeyCode, MouseButton};
 use crate::data::maps::Map;
 use crate::graphics::renderer::*;
 use crate::algos::Algo;
@@ -19,6 +19,8 @@ pub struct AlgoPicker {
     params: AlgoParams,
     selected: Option<usize>,
     highlighted: usize,
+    //where the algorithms were last drawn
+    layout: ListLayout,
 }
 
 impl AlgoPicker {
@@ -26,11 +28,18 @@ impl AlgoPicker {
         AlgoPicker {
             params: AlgoParams { map, variant },
             selected: None,
-            highlighted: *cursor_mem.get(CURSOR_ID).unwrap_or(&0)
+            highlighted: *cursor_mem.get(CURSOR_ID).unwrap_or(&0),
+            layout: ListLayout::default(),
         }
     }
 }
 
+impl AlgoPicker {
+    fn is_available(&self, algo: Algo) -> bool {
+        !algo.requires_uniform_cost() || self.params.map.is_uniform_cost()
+    }
+}
+
 impl Scene for AlgoPicker {
     fn update(&mut self, _ctx: &mut Context) -> Result<(), GameError> {
         Ok(())
@@ -41,9 +50,15 @@ impl Scene for AlgoPicker {
         let line_height = renderer.calc_height(0.05);
 
         let list_mesh = renderer.make_list_indicator_mesh(ctx, renderer.calc_height(0.03))?;
+        self.layout = ListLayout::new(renderer.calc_width(0.16), offset.1, renderer.calc_width(0.6), line_height, Algo::len());
 
         for i in 0..Algo::len() {
-            renderer.draw_white_text(ctx, Algo::from_index(i).name(), point(offset.0, offset.1 + (line_height * i as f32)), renderer.calc_height(0.04), false);
+            let algo = Algo::from_index(i);
+            if self.is_available(algo) {
+                renderer.draw_white_text(ctx, algo.name(), point(offset.0, offset.1 + (line_height * i as f32)), renderer.calc_height(0.04), false);
+            } else {
+                renderer.draw_text(ctx, format!("{} (map must not have tile costs)", algo.name()), point(offset.0, offset.1 + (line_height * i as f32)), (0.5, 0.5, 0.5, 1.).into(), renderer.calc_height(0.04), false);
+            }
         }
 
         renderer.draw_mesh(ctx, list_mesh.as_ref(), point(renderer.calc_width(0.16), offset.1 + renderer.calc_height(0.008) + (self.highlighted as f32 * line_height)));
@@ -71,11 +86,35 @@ impl Scene for AlgoPicker {
 
     fn on_button_up(&mut self, keycode: KeyCode) {
         match keycode {
-            KeyCode::Return => self.selected = Some(self.highlighted),
+            KeyCode::Return => {
+                if self.is_available(Algo::from_index(self.highlighted)) {
+                    self.selected = Some(self.highlighted);
+                }
+            }
             _ => {}
         }
     }
 
+    fn on_mouse_click(&mut self, position: DPPoint, button: MouseButton) {
+        if button != MouseButton::Left {
+            return;
+        }
+        if let Some(row) = self.layout.row_at(position) {
+            self.highlighted = row;
+            if self.is_available(Algo::from_index(row)) {
+                self.selected = Some(row);
+            }
+        }
+    }
+
+    fn on_mouse_scroll(&mut self, amount: f32) {
+        if amount > 0. {
+            self.on_button_down(KeyCode::Up);
+        } else if amount < 0. {
+            self.on_button_down(KeyCode::Down);
+        }
+    }
+
     fn is_complete(&self) -> bool {
         return self.selected.is_some();
     }
@@ -0,0 +1,177 @@
+use crate::{point, DPPoint};
+use crate::data::Coord;
+use crate::scenes::{Scene, SceneParams, ListLayout};
+use ggez::{Context, GameError};
+use ggez::event::{KeyCode, MouseButton};
+use crate::data::maps::{Map, Variant, NODE_WALL};
+use crate::graphics::renderer::Renderer;
+use crate::data::diagonal::Diagonal;
+use crate::data::cost_model::CostModel;
+use crate::data::cost::Cost;
+use crate::algos::{Algo, Algorithm, AlgoStatus, Path, path_cost};
+use crate::algos::dijkstra::Dijkstra;
+use crate::graphics::map_rendering::draw_map_with_costs_path;
+use std::rc::Rc;
+use std::collections::HashMap;
+
+const CURSOR_ID: &'static str = "cost_model_highlighted";
+//Cost of tile entered, used when the algorithm doesn't add up costs
+const DEFAULT_MODEL: usize = 1;
+
+struct CostModelParams {
+    map: Rc<Map>,
+    algo: Algo,
+    diagonal: Diagonal,
+    variant: usize,
+}
+
+pub struct CostModelPicker {
+    params: CostModelParams,
+    selected: Option<usize>,
+    highlighted: usize,
+    //where the options were last drawn
+    layout: ListLayout,
+    example_map: Map,
+    //Cheapest path across the example map and its cost for each cost model
+    example_paths: Vec<(Path, Cost)>,
+}
+
+impl CostModelPicker {
+    pub fn new(map: Rc<Map>, algo: Algo, diagonal: Diagonal, variant: usize, cursor_mem: &HashMap<&str, usize>) -> CostModelPicker {
+        let example_map = Map {
+            variants: vec![Variant { start: Coord::new(0, 1), ends: vec![Coord::new(6, 2)] }],
+            cost: vec![vec![5, 0, 0, 1], vec![0, 0, NODE_WALL, NODE_WALL], vec![1, 2, 3, 0], vec![0, 0, 1, 8], vec![0, 5, 0, 0], vec![4, 7, 3, 8], vec![8, 5, 0, 7]],
+        };
+        let example_paths = (0..CostModel::len())
+            .map(|idx| find_example_path(&example_map, diagonal, CostModel::from_index(idx)))
+            .collect();
+        let selected = if algo.uses_cost_model() { None } else { Some(DEFAULT_MODEL) };
+        CostModelPicker {
+            params: CostModelParams { map, algo, diagonal, variant },
+            selected,
+            highlighted: *cursor_mem.get(CURSOR_ID).unwrap_or(&DEFAULT_MODEL),
+            layout: ListLayout::default(),
+            example_map,
+            example_paths,
+        }
+    }
+}
+
+fn find_example_path(map: &Map, diagonal: Diagonal, cost_model: CostModel) -> (Path, Cost) {
+    let columns = map.get_column_count() as i32;
+    let rows = map.get_row_count() as i32;
+    let make_cost_calc = || {
+        let cost = map.cost.clone();
+        Box::new(move |xy: Coord| {
+            if xy.is_out_of_bounds(columns, rows) {
+                -1
+            } else {
+                cost[xy.x as usize][xy.y as usize]
+            }
+        })
+    };
+    let variant = &map.variants[0];
+    let mut dijkstra = Dijkstra::new_fixed_target(variant.start, variant.ends.clone(), make_cost_calc(), columns, rows, diagonal, cost_model);
+    loop {
+        dijkstra.tick();
+        match dijkstra.get_data() {
+            AlgoStatus::InProgress(_) => {}
+            AlgoStatus::Found(path, _) => {
+                let coords = path.coords().clone();
+                let cost = path_cost(cost_model, make_cost_calc().as_ref(), &coords);
+                return (Path::Tiles(coords), cost);
+            }
+            AlgoStatus::NoPath => return (Path::Tiles(vec![]), Cost::ZERO),
+        }
+    }
+}
+
+impl Scene for CostModelPicker {
+    fn update(&mut self, _ctx: &mut Context) -> Result<(), GameError> {
+        Ok(())
+    }
+
+    fn render(&mut self, ctx: &mut Context, renderer: &mut Renderer) -> Result<(), GameError> {
+        let title_offset = renderer.calc_percent_to_point(0.19, 0.05);
+        let text_offset = renderer.calc_percent_to_point(0.19, 0.14);
+        let text_spacing = renderer.calc_height(0.05);
+
+        let cell_size = renderer.calc_width(0.03);
+        let grid_offset = renderer.calc_percent_to_point(0.2, 0.5);
+        let cost_offset = point(grid_offset.x, grid_offset.y + cell_size * self.example_map.get_row_count() as f32 + renderer.calc_height(0.02));
+
+        let list_mesh = renderer.make_list_indicator_mesh(ctx, renderer.calc_height(0.03))?;
+        self.layout = ListLayout::new(text_offset.x - text_spacing, text_offset.y, renderer.calc_width(0.6), text_spacing, CostModel::len());
+
+        renderer.draw_white_text(ctx, "Choose how tile costs are counted", title_offset, renderer.calc_height(0.04), false);
+
+        for i in 0..CostModel::len() {
+            renderer.draw_white_text(ctx, CostModel::from_index(i).name(), point(text_offset.x, text_offset.y + (text_spacing * i as f32)), renderer.calc_height(0.04), false);
+        }
+
+        renderer.draw_mesh(ctx, list_mesh.as_ref(), point(text_offset.x - text_spacing, text_offset.y + renderer.calc_height(0.008) + (self.highlighted as f32 * text_spacing)));
+
+        let (path, cost) = &self.example_paths[self.highlighted];
+        draw_map_with_costs_path(ctx, renderer, (grid_offset.x, grid_offset.y), cell_size, &self.example_map, path, &vec![], 0)?;
+        renderer.draw_white_text(ctx, format!("Path cost: {}", cost), cost_offset, renderer.calc_height(0.03), false);
+
+        Ok(())
+    }
+
+    fn on_button_down(&mut self, keycode: KeyCode) {
+        match keycode {
+            KeyCode::Up => {
+                if self.highlighted > 0 {
+                    self.highlighted -= 1;
+                }
+            }
+            KeyCode::Down => {
+                if self.highlighted < CostModel::len() - 1 {
+                    self.highlighted += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_button_up(&mut self, keycode: KeyCode) {
+        match keycode {
+            KeyCode::Return => self.selected = Some(self.highlighted),
+            _ => {}
+        }
+    }
+
+    fn on_mouse_click(&mut self, position: DPPoint, button: MouseButton) {
+        if button != MouseButton::Left {
+            return;
+        }
+        if let Some(row) = self.layout.row_at(position) {
+            self.highlighted = row;
+            self.selected = Some(row);
+        }
+    }
+
+    fn on_mouse_scroll(&mut self, amount: f32) {
+        if amount > 0. {
+            self.on_button_down(KeyCode::Up);
+        } else if amount < 0. {
+            self.on_button_down(KeyCode::Down);
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        return self.selected.is_some();
+    }
+
+    fn get_next_stage_params(&self, cursor_mem: &mut HashMap<&str, usize>) -> SceneParams {
+        cursor_mem.insert(CURSOR_ID, self.highlighted);
+        let cost_model = CostModel::from_index(self.selected.expect("Nothing selected"));
+        SceneParams::HeuristicSelection {
+            map: self.params.map.clone(),
+            algo: self.params.algo,
+            diagonal: self.params.diagonal,
+            cost_model,
+            variant: self.params.variant,
+        }
+    }
+}
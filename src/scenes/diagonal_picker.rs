@@ -1,12 +1,12 @@
 use crate::{point, DPPoint};
 use crate::data::Coord;
-use crate::scenes::{Scene, SceneParams};
+use crate::scenes::{Scene, SceneParams, ListLayout};
 use ggez::{Context, GameError, GameResult};
-use ggez::event::KeyCode;
+use ggez::event::{KeyCode, MouseButton};
 use crate::data::maps::{Map, Variant};
 use crate::graphics::renderer::*;
 use crate::data::diagonal::Diagonal;
-use crate::algos::Algo;
+use crate::algos::{Algo, Path};
 use crate::graphics::map_rendering::draw_map_with_costs_path;
 use std::rc::Rc;
 use std::collections::HashMap;
@@ -23,7 +23,9 @@ pub struct DiagonalPicker {
     params: DiagonalParams,
     selected: Option<usize>,
     highlighted: usize,
-    diagonal_maps: Vec<(Map, Vec<Coord>)>,
+    //where the options were last drawn
+    layout: ListLayout,
+    diagonal_maps: Vec<(Map, Path)>,
 }
 
 impl DiagonalPicker {
@@ -32,19 +34,20 @@ impl DiagonalPicker {
             params: DiagonalParams { map, algo, variant },
             selected: None,
             highlighted: *cursor_mem.get(CURSOR_ID).unwrap_or(&0),
+            layout: ListLayout::default(),
             diagonal_maps: vec![
                 (Map {
                     variants: vec![Variant { start: Coord::new(0, 0), ends: vec![Coord::new(3, 3)] }],
                     cost: vec![vec![0, 0, 0, 0], vec![0, 0, 0, 0], vec![0, 0, 0, 0], vec![0, 0, 0, 0]],
-                }, vec![Coord::new(0, 0), Coord::new(1, 1), Coord::new(2, 2), Coord::new(3, 3)]),
+                }, Path::Tiles(vec![Coord::new(0, 0), Coord::new(1, 1), Coord::new(2, 2), Coord::new(3, 3)])),
                 (Map {
                     variants: vec![Variant { start: Coord::new(0, 0), ends: vec![Coord::new(3, 3)] }],
                     cost: vec![vec![0, 0, 0, 9], vec![0, 0, 9, 0], vec![0, 9, 0, 0], vec![0, 0, 0, 0]],
-                }, vec![Coord::new(0, 0), Coord::new(1, 0), Coord::new(2, 0), Coord::new(3, 1), Coord::new(3, 2), Coord::new(3, 3)]),
+                }, Path::Tiles(vec![Coord::new(0, 0), Coord::new(1, 0), Coord::new(2, 0), Coord::new(3, 1), Coord::new(3, 2), Coord::new(3, 3)])),
                 (Map {
                     variants: vec![Variant { start: Coord::new(0, 0), ends: vec![Coord::new(3, 3)] }],
                     cost: vec![vec![0, 0, 0, 9], vec![0, 0, 9, 0], vec![0, 9, 0, 0], vec![9, 0, 0, 0]],
-                }, vec![Coord::new(0, 0), Coord::new(1, 1), Coord::new(2, 2), Coord::new(3, 3)])
+                }, Path::Tiles(vec![Coord::new(0, 0), Coord::new(1, 1), Coord::new(2, 2), Coord::new(3, 3)]))
             ],
         }
     }
@@ -85,6 +88,7 @@ impl Scene for DiagonalPicker {
         let marker_spacing = (grid_spacing + grid_width) as f32;
 
         let list_mesh = renderer.make_list_indicator_mesh(ctx, renderer.calc_height(0.03))?;
+        self.layout = ListLayout::new(text_offset.x - text_spacing, text_offset.y, renderer.calc_width(0.6), text_spacing, Diagonal::len());
 
         renderer.draw_white_text(ctx, "Choose how to handle diagonals", title_offset, renderer.calc_height(0.04), false);
 
@@ -139,6 +143,24 @@ impl Scene for DiagonalPicker {
         }
     }
 
+    fn on_mouse_click(&mut self, position: DPPoint, button: MouseButton) {
+        if button != MouseButton::Left {
+            return;
+        }
+        if let Some(row) = self.layout.row_at(position) {
+            self.highlighted = row;
+            self.selected = Some(row);
+        }
+    }
+
+    fn on_mouse_scroll(&mut self, amount: f32) {
+        if amount > 0. {
+            self.on_button_down(KeyCode::Up);
+        } else if amount < 0. {
+            self.on_button_down(KeyCode::Down);
+        }
+    }
+
     fn is_complete(&self) -> bool {
         return self.selected.is_some();
     }
@@ -146,7 +168,7 @@ impl Scene for DiagonalPicker {
     fn get_next_stage_params(&self, cursor_mem: &mut HashMap<&str, usize>) -> SceneParams {
         cursor_mem.insert(CURSOR_ID, self.highlighted);
         let diagonal = Diagonal::from_index(self.selected.expect("Nothing selected"));
-        SceneParams::HeuristicSelection {
+        SceneParams::CostModelSelection {
             map: self.params.map.clone(),
             algo: self.params.algo,
             variant: self.params.variant,
@@ -1,47 +1,78 @@
 use std::rc::Rc;
-use crate::data::maps::Map;
+use crate::data::maps::{Map, SharedCost, NODE_WALL, NODE_FREE};
+use crate::data::Coord;
+use crate::data::cost_model::CostModel;
 use crate::algos::Algorithm;
 use crate::scenes::{Scene, SceneParams::EndOfProgram};
-use crate::algos::AlgoStatus;
+use crate::data::cost::Cost;
+use crate::algos::{AlgoStatus, Path, path_cost};
+use crate::algos::search_tracker::SearchTracker;
+use crate::algos::timeline::Timeline;
 use crate::graphics::renderer::Renderer;
 use crate::scenes::SceneParams;
-use crate::{max, GRID_HORZ_COUNT, GRID_VERT_COUNT};
+use crate::max;
 use ggez::{Context, GameError, timer};
-use ggez::event::KeyCode;
+use ggez::event::{KeyCode, MouseButton};
 use std::cell::RefCell;
-use crate::{point, SCREEN_WIDTH, SCREEN_HEIGHT};
+use crate::{point, DPPoint, SCREEN_WIDTH, SCREEN_HEIGHT};
 use ggez::graphics::{Text, TextFragment, Color, Scale, MeshBuilder, DrawMode, Rect};
-use crate::graphics::map_rendering::{draw_map_with_costs_nodes, draw_map_with_costs_path, draw_map_with_costs_start_end};
+use crate::graphics::map_rendering::{fit_cell_size, draw_map_with_costs_nodes, draw_map_with_costs_path, draw_map_with_costs_start_end, draw_map_jump_points, draw_map_legs};
 use std::collections::HashMap;
 
+const WEIGHT_STEP: f32 = 0.25;
+
 pub struct Executor {
     map_id: usize,
     map: Rc<Map>,
+    costs: SharedCost,
+    cursor: Coord,
     algo: Rc<RefCell<Box<dyn Algorithm>>>,
+    //every tick so far, so earlier ones can be shown again
+    timeline: Timeline,
+    //which tick is being shown, the latest one unless scrubbing back
+    shown_tick: usize,
+    //search state at the shown tick
+    tracker: SearchTracker,
+    //where the mouse was last seen, the tile under it is inspected
+    mouse: Option<DPPoint>,
+    //where the timeline was last drawn, so it can be clicked and dragged
+    timeline_bar: Option<Rect>,
+    dragging_timeline: bool,
     diagonal_mode: String,
+    cost_model: CostModel,
     heuristic_mode: String,
     auto_advance: bool,
     advance: bool,
     update_speed: f64,
     last_update: f64,
-    ticks: usize,
     algo_name: String,
     variant: usize
 }
 
 impl Executor {
-    pub fn new(map: Rc<Map>, algo: Rc<RefCell<Box<dyn Algorithm>>>, algo_name: String, diagonal_mode: String, heuristic_mode: String, variant: usize, _cursor_mem: &HashMap<&str, usize>) -> Executor {
+    pub fn new(map: Rc<Map>, costs: SharedCost, algo: Rc<RefCell<Box<dyn Algorithm>>>, algo_name: String, diagonal_mode: String, cost_model: CostModel, heuristic_mode: String, variant: usize, _cursor_mem: &HashMap<&str, usize>) -> Executor {
+        let cursor = map.variants[variant].start;
+        let timeline = Timeline::new(algo.borrow_mut().as_mut());
+        let tracker = timeline.tracker_at(0);
         Executor {
             map_id: 0,
             map,
+            costs,
+            cursor,
             algo,
+            timeline,
+            shown_tick: 0,
+            tracker,
+            mouse: None,
+            timeline_bar: None,
+            dragging_timeline: false,
             diagonal_mode,
+            cost_model,
             heuristic_mode,
             auto_advance: true,
             advance: false,
             update_speed: 0.2,
             last_update: 0.,
-            ticks: 0,
             algo_name,
             variant
         }
@@ -49,23 +80,204 @@ impl Executor {
 }
 
 impl Executor {
-    fn draw_info_text(&mut self, ctx: &mut Context, renderer: &mut Renderer) {
+    fn draw_info_text(&self, ctx: &mut Context, renderer: &mut Renderer, status: &AlgoStatus) {
         let advancing_text;
         if self.auto_advance {
             advancing_text = format!("Automatic at {:.1}s", self.update_speed);
         } else {
             advancing_text = String::from("Manual");
         }
-        let step_text= match self.algo.borrow().get_data() {
-            AlgoStatus::InProgress(_) => format!("{} | Tick {}", advancing_text, self.ticks),
-            AlgoStatus::Found(path, _) => format!("Found: {} ticks, Path: {} tiles", self.ticks, path.len()),
-            AlgoStatus::NoPath => format!("Failed after {} ticks", self.ticks)
+        let tick_text = if self.is_live() {
+            format!("{}", self.shown_tick)
+        } else {
+            format!("{} of {}", self.shown_tick, self.timeline.last_tick())
+        };
+        let step_text= match status {
+            AlgoStatus::InProgress(_) => format!("{} | Tick {}", advancing_text, tick_text),
+            AlgoStatus::Found(Path::Tiles(tiles), _) => format!("Found: {} ticks, Path: {} tiles, costs {}", tick_text, tiles.len(), self.path_cost(tiles)),
+            AlgoStatus::Found(path, _) => format!("Found: {} ticks, Path: {} waypoints, {:.1} tiles long", tick_text, path.coords().len(), path.length()),
+            AlgoStatus::NoPath => format!("Failed after {} ticks", tick_text)
         };
-        let display = format!("Map: {}  Algo: {}  Diag: {}  Heur: {}  |  {}", self.map_id, self.algo_name, self.diagonal_mode, self.heuristic_mode, step_text);
+        let mut display = format!("Map: {}  Algo: {}  Diag: {}  Cost: {}  Heur: {}  |  {}", self.map_id, self.algo_name, self.diagonal_mode, self.cost_model.name(), self.heuristic_mode, step_text);
+        if let Some(weight) = self.algo.borrow().get_weight() {
+            display.push_str(&format!("  |  Weight: {:.2}", weight));
+        }
+        if let Some((threshold, iteration)) = self.algo.borrow().get_threshold() {
+            display.push_str(&format!("  |  Threshold: {}  Iteration: {}", threshold, iteration));
+        }
+        if let Some(legs) = self.algo.borrow().get_legs().filter(|_| self.is_live()) {
+            match status {
+                AlgoStatus::InProgress(_) => display.push_str(&format!("  |  Leg {} of {}", legs.len() + 1, self.map.variants[self.variant].ends.len())),
+                _ => display.push_str(&format!("  |  Tour legs: {}", legs.len())),
+            }
+        }
+        if let Some(ratio) = self.algo.borrow().get_cost_ratio() {
+            display.push_str(&format!(" ({:.2}x optimal)", ratio));
+        }
+        if let Some((xy, tile)) = self.tracker.last_closed() {
+            let parent = tile.parent.map(|parent| format!(" from {}", parent)).unwrap_or_default();
+            display.push_str(&format!("  |  Closed {}{}  g {}  h {}", xy, parent, tile.g, tile.h));
+        }
         renderer.draw_white_text(ctx, display, point(8., 4.), renderer.calc_height(0.04), false);
     }
 }
 
+impl Executor {
+    //Bar under the map showing which of the recorded ticks is shown, returns the area that can be clicked
+    fn draw_timeline(&self, ctx: &mut Context, renderer: &mut Renderer, position: (f32, f32), width: f32) -> Result<Option<Rect>, GameError> {
+        let last_tick = self.timeline.last_tick();
+        if last_tick == 0 {
+            return Ok(None);
+        }
+        let height = renderer.calc_height(0.01);
+        let marker_width = renderer.calc_width(0.005);
+        let track = MeshBuilder::new().rectangle(DrawMode::fill(), Rect::new(0., 0., width, height), (0.3, 0.3, 0.3, 1.).into()).build(ctx)?;
+        let marker = MeshBuilder::new().rectangle(DrawMode::fill(), Rect::new(0., 0., marker_width, height * 3.), (1., 1., 1., 1.).into()).build(ctx)?;
+        let progress = self.shown_tick as f32 / last_tick as f32;
+        let y = position.1 + renderer.calc_height(0.03);
+        renderer.draw_mesh(ctx, &track, point(position.0, y));
+        renderer.draw_mesh(ctx, &marker, point(position.0 + (width - marker_width) * progress, y - height));
+        Ok(Some(Rect::new(position.0, y - height, width, height * 3.)))
+    }
+
+    //Tick under `x` on the timeline, the ends of the bar are the first and latest ticks
+    fn tick_at(&self, bar: Rect, x: f32) -> usize {
+        let progress = ((x - bar.x) / bar.w).clamp(0., 1.);
+        return (progress * self.timeline.last_tick() as f32).round() as usize;
+    }
+
+    //Tile under the mouse, if it's over the map
+    fn hovered_tile(&self, grid_start: (f32, f32), cell_size: f32) -> Option<Coord> {
+        let mouse = self.mouse?;
+        let x = ((mouse.x - grid_start.0) / cell_size).floor();
+        let y = ((mouse.y - grid_start.1) / cell_size).floor();
+        let xy = Coord::new(x as i32, y as i32);
+        if x < 0. || y < 0. || xy.is_out_of_bounds(self.map.get_column_count() as i32, self.map.get_row_count() as i32) {
+            return None;
+        }
+        return Some(xy);
+    }
+
+    //What's known about `xy` at the shown tick, next to the tile
+    fn draw_inspector(&self, ctx: &mut Context, renderer: &mut Renderer, xy: Coord, position: DPPoint) -> Result<(), GameError> {
+        let tile_cost = self.map.cost[xy.x as usize][xy.y as usize];
+        let mut lines = vec![];
+        if tile_cost == NODE_WALL {
+            lines.push(format!("{}  wall", xy));
+        } else {
+            lines.push(format!("{}  tile cost {}", xy, tile_cost));
+        }
+        let tiles = self.tracker.tiles(xy);
+        if tiles.is_empty() {
+            lines.push(String::from(if self.tracker.was_passed(xy) { "jumped over" } else { "not reached" }));
+        }
+        for (frontier, tile) in tiles {
+            //searches from several places at once know different things about the same tile
+            if self.tracker.frontier_count() > 1 {
+                lines.push(format!("frontier {}", frontier + 1));
+            }
+            lines.push(format!("g {}  h {}  f {}", tile.g, tile.h, tile.f));
+            lines.push(match tile.parent {
+                Some(parent) => format!("parent {}", parent),
+                None => String::from("no parent"),
+            });
+            lines.push(match tile.closed_tick {
+                Some(closed) => format!("opened tick {}, closed tick {}", tile.opened_tick, closed),
+                None => format!("opened tick {}, still open", tile.opened_tick),
+            });
+        }
+        let font_size = renderer.calc_height(0.03);
+        let width = renderer.calc_width(0.2);
+        let height = font_size * lines.len() as f32 + 8.;
+        //kept on screen when the tile is near the right or bottom edge
+        let x = position.x.min(SCREEN_WIDTH - width);
+        let y = position.y.min(SCREEN_HEIGHT - height);
+        let background = MeshBuilder::new().rectangle(DrawMode::fill(), Rect::new(0., 0., width, height), (0., 0., 0., 0.85).into()).build(ctx)?;
+        renderer.draw_mesh(ctx, &background, point(x, y));
+        for (i, line) in lines.into_iter().enumerate() {
+            renderer.draw_white_text(ctx, line, point(x + 4., y + 4. + font_size * i as f32), font_size, false);
+        }
+        Ok(())
+    }
+
+    //Cost of the path in tiles, a free cardinal step costing 1
+    fn path_cost(&self, tiles: &[Coord]) -> Cost {
+        let map = self.map.clone();
+        return path_cost(self.cost_model, &move |xy: Coord| map.cost[xy.x as usize][xy.y as usize], tiles);
+    }
+
+    fn can_edit(&self) -> bool {
+        self.algo.borrow().supports_cost_changes()
+    }
+
+    fn move_cursor(&mut self, x: i32, y: i32) {
+        let new_cursor = self.cursor + (x, y);
+        if !new_cursor.is_out_of_bounds(self.map.get_column_count() as i32, self.map.get_row_count() as i32) {
+            self.cursor = new_cursor;
+        }
+    }
+
+    fn change_weight(&mut self, change: f32) {
+        let weight = self.algo.borrow().get_weight();
+        if let Some(weight) = weight {
+            self.algo.borrow_mut().set_weight((weight + change).max(0.));
+            self.timeline = Timeline::new(self.algo.borrow_mut().as_mut());
+            self.shown_tick = 0;
+            self.tracker = self.timeline.tracker_at(0);
+        }
+    }
+
+    fn toggle_wall(&mut self) {
+        let variant = &self.map.variants[self.variant];
+        if variant.start == self.cursor || variant.ends.contains(&self.cursor) {
+            return;
+        }
+        {
+            let mut costs = self.costs.borrow_mut();
+            let cost = &mut costs[self.cursor.x as usize][self.cursor.y as usize];
+            *cost = if *cost == NODE_WALL { NODE_FREE } else { NODE_WALL };
+        }
+        self.map = Rc::new(Map {
+            variants: self.map.variants.clone(),
+            cost: self.costs.borrow().clone(),
+        });
+        self.algo.borrow_mut().cost_changed(self.cursor);
+        self.timeline.update_latest(self.algo.borrow_mut().as_mut());
+        self.tracker = self.timeline.tracker_at(self.shown_tick);
+    }
+}
+
+impl Executor {
+    fn is_live(&self) -> bool {
+        self.shown_tick == self.timeline.last_tick()
+    }
+
+    fn show_tick(&mut self, tick: usize) {
+        let tick = tick.min(self.timeline.last_tick());
+        self.timeline.replay(&mut self.tracker, self.shown_tick, tick);
+        self.shown_tick = tick;
+    }
+
+    //Shows the next recorded tick, or runs a new one if the latest is already shown
+    fn step_forward(&mut self) {
+        if !self.is_live() {
+            self.show_tick(self.shown_tick + 1);
+            return;
+        }
+        let in_progress = matches!(self.algo.borrow().get_data(), AlgoStatus::InProgress(_));
+        if in_progress {
+            self.algo.borrow_mut().tick();
+            self.timeline.record(self.algo.borrow_mut().as_mut());
+            self.show_tick(self.timeline.last_tick());
+        }
+    }
+
+    fn step_back(&mut self) {
+        self.auto_advance = false;
+        self.show_tick(self.shown_tick.saturating_sub(1));
+    }
+}
+
 impl Scene for Executor {
     fn update(&mut self, ctx: &mut Context) -> Result<(), GameError> {
         if !self.auto_advance && !self.advance {
@@ -76,28 +288,37 @@ impl Scene for Executor {
         if self.advance || (self.last_update + self.update_speed) < time {
             self.last_update = time;
 
-            self.algo.borrow_mut().tick();
-            match self.algo.borrow().get_data() {
-                AlgoStatus::InProgress(_) => self.ticks += 1,
-                _ => {}
-            }
+            self.step_forward();
         }
         Ok(())
     }
 
     fn render(&mut self, ctx: &mut Context, renderer: &mut Renderer) -> Result<(), GameError> {
-        let cell_size = renderer.calc_width(0.03);
-        let grid_width = cell_size * (GRID_HORZ_COUNT as f32);
-        let grid_height = cell_size * (GRID_VERT_COUNT as f32);
+        let cell_size = fit_cell_size(self.map.as_ref(), renderer.calc_percent_to_px(0.96, 0.84));
+        let grid_width = cell_size * (self.map.get_column_count() as f32);
+        let grid_height = cell_size * (self.map.get_row_count() as f32);
         let x = renderer.calc_width(0.5) - (grid_width * 0.5);
-        let y = renderer.calc_height(0.5) - (grid_height * 0.5) + (cell_size * 0.5);
+        let y = renderer.calc_height(0.5) - (grid_height * 0.5) + renderer.calc_height(0.02);
         let grid_start = (x, y);
-        match self.algo.borrow().get_data() {
-            AlgoStatus::InProgress((open_nodes, closed_nodes)) => {
-                draw_map_with_costs_nodes(ctx, renderer, grid_start, cell_size, self.map.clone().as_ref(), open_nodes, closed_nodes, self.variant)?;
+        let status = &self.tracker.status();
+        let algo = self.algo.borrow();
+        //legs and jump points are only known for the latest tick
+        let live = self.is_live();
+        match status {
+            AlgoStatus::InProgress(frontiers) => {
+                draw_map_with_costs_nodes(ctx, renderer, grid_start, cell_size, self.map.clone().as_ref(), frontiers, self.variant)?;
+                if let Some(legs) = algo.get_legs().filter(|_| live) {
+                    draw_map_legs(ctx, renderer, grid_start, cell_size, legs)?;
+                }
+                if let Some(jump_points) = algo.get_jump_points().filter(|_| live) {
+                    draw_map_jump_points(ctx, renderer, grid_start, cell_size, jump_points)?;
+                }
             }
-            AlgoStatus::Found(path, closed_nodes) => {
-                draw_map_with_costs_path(ctx, renderer, grid_start, cell_size, self.map.clone().as_ref(), &path, closed_nodes, self.variant)?;
+            AlgoStatus::Found(path, frontiers) => {
+                draw_map_with_costs_path(ctx, renderer, grid_start, cell_size, self.map.clone().as_ref(), &path, frontiers, self.variant)?;
+                if let Some(jump_points) = algo.get_jump_points().filter(|_| live) {
+                    draw_map_jump_points(ctx, renderer, grid_start, cell_size, jump_points)?;
+                }
             }
             AlgoStatus::NoPath => {
                 let text = Text::new(TextFragment {
@@ -114,7 +335,20 @@ impl Scene for Executor {
             }
         }
 
-        self.draw_info_text(ctx, renderer);
+        if self.can_edit() {
+            let cursor_mesh = renderer.make_square_mesh(ctx, cell_size, false, 3.)?;
+            renderer.draw_coloured_mesh(ctx, cursor_mesh.as_ref(), point(grid_start.0 + (self.cursor.x as f32 * cell_size), grid_start.1 + (self.cursor.y as f32 * cell_size)), (1., 1., 0., 1.).into());
+        }
+
+        self.timeline_bar = self.draw_timeline(ctx, renderer, (grid_start.0, grid_start.1 + grid_height), grid_width)?;
+        self.draw_info_text(ctx, renderer, status);
+
+        if let Some(xy) = self.hovered_tile(grid_start, cell_size) {
+            let tile_position = point(grid_start.0 + (xy.x as f32 * cell_size), grid_start.1 + (xy.y as f32 * cell_size));
+            let hover_mesh = renderer.make_square_mesh(ctx, cell_size, false, 2.)?;
+            renderer.draw_coloured_mesh(ctx, hover_mesh.as_ref(), tile_position, (0., 1., 1., 1.).into());
+            self.draw_inspector(ctx, renderer, xy, point(tile_position.x + cell_size, tile_position.y + cell_size))?;
+        }
 
         Ok(())
     }
@@ -126,18 +360,55 @@ impl Scene for Executor {
                 self.auto_advance = false;
                 self.advance = true;
             }
+            KeyCode::Comma => self.step_back(),
+            KeyCode::Period => {
+                self.auto_advance = false;
+                self.step_forward();
+            }
+            KeyCode::Home => {
+                self.auto_advance = false;
+                self.show_tick(0);
+            }
+            KeyCode::End => self.show_tick(self.timeline.last_tick()),
             KeyCode::LBracket => {
                 self.update_speed = max(0., self.update_speed - 0.05);
             }
             KeyCode::RBracket => {
                 self.update_speed = max(0., self.update_speed + 0.05);
             }
+            KeyCode::Up if self.can_edit() => self.move_cursor(0, -1),
+            KeyCode::Down if self.can_edit() => self.move_cursor(0, 1),
+            KeyCode::Left if self.can_edit() => self.move_cursor(-1, 0),
+            KeyCode::Right if self.can_edit() => self.move_cursor(1, 0),
+            KeyCode::W if self.can_edit() && self.is_live() => self.toggle_wall(),
+            KeyCode::Minus => self.change_weight(-WEIGHT_STEP),
+            KeyCode::Equals => self.change_weight(WEIGHT_STEP),
             _ => {}
         }
     }
 
     fn on_button_up(&mut self, _keycode: KeyCode) {}
 
+    fn on_mouse_move(&mut self, position: DPPoint) {
+        self.mouse = Some(position);
+        if let Some(bar) = self.timeline_bar.filter(|_| self.dragging_timeline) {
+            self.show_tick(self.tick_at(bar, position.x));
+        }
+    }
+
+    //Clicking the timeline jumps to that tick, holding the button down drags through the ticks
+    fn on_mouse_down(&mut self, position: DPPoint, button: MouseButton) {
+        if let Some(bar) = self.timeline_bar.filter(|bar| button == MouseButton::Left && bar.contains(position)) {
+            self.auto_advance = false;
+            self.dragging_timeline = true;
+            self.show_tick(self.tick_at(bar, position.x));
+        }
+    }
+
+    fn on_mouse_click(&mut self, _position: DPPoint, _button: MouseButton) {
+        self.dragging_timeline = false;
+    }
+
     fn is_complete(&self) -> bool {
         false
     }
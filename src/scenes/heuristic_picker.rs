@@ -1,25 +1,38 @@
 use ggez::{Context, GameError};
-use ggez::event::KeyCode;
-use crate::point;
-use crate::data::maps::Map;
+use ggez::event::{KeyCode, MouseButton};
+use crate::{point, DPPoint};
+use crate::data::maps::{Map, SharedCost};
 use crate::data::Coord;
 use crate::graphics::renderer::Renderer;
 use crate::data::diagonal::Diagonal;
+use crate::data::cost_model::CostModel;
 use crate::algos::{Algo, Algorithm};
-use crate::algos::astar::Astar;
-use crate::data::heuristic::Heuristic;
+use crate::algos::astar::{Astar, DEFAULT_WEIGHT};
+use crate::data::heuristic::{Heuristic, Combination};
 use crate::algos::dijkstra::Dijkstra;
-use crate::scenes::{Scene, SceneParams};
+use crate::algos::dstar_lite::DStarLite;
+use crate::algos::bfs::Bfs;
+use crate::algos::bidirectional::Bidirectional;
+use crate::algos::greedy::Greedy;
+use crate::algos::ida_star::IdaStar;
+use crate::algos::jps::Jps;
+use crate::algos::theta::Theta;
+use crate::algos::tour::Tour;
+use crate::scenes::{Scene, SceneParams, ListLayout};
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::collections::HashMap;
 
 const CURSOR_ID: &'static str = "heuristic_highlighted";
+const COMBINATION_CURSOR_ID: &'static str = "heuristic_combination";
+//Diagonal mode the remembered cursor was picked with, the suggested heuristic is highlighted instead when it changes
+const DIAGONAL_CURSOR_ID: &'static str = "heuristic_diagonal";
 
 pub struct HeuristicParams {
     map: Rc<Map>,
     algo: Algo,
     diagonal: Diagonal,
+    cost_model: CostModel,
     variant: usize,
 }
 
@@ -27,33 +40,53 @@ pub struct HeuristicPicker {
     params: HeuristicParams,
     selected: Option<usize>,
     highlighted: usize,
+    //where the options were last drawn
+    layout: ListLayout,
+    combination: usize,
 }
 
 impl HeuristicPicker {
-    pub fn new(map: Rc<Map>, algo: Algo, diagonal: Diagonal, variant: usize, cursor_mem: &HashMap<&str, usize>) -> HeuristicPicker {
+    pub fn new(map: Rc<Map>, algo: Algo, diagonal: Diagonal, cost_model: CostModel, variant: usize, cursor_mem: &HashMap<&str, usize>) -> HeuristicPicker {
         let params = HeuristicParams {
             map,
             algo,
             diagonal,
+            cost_model,
             variant,
         };
-        let highlighted = *cursor_mem.get(CURSOR_ID).unwrap_or(&0);
+        let suggested = Heuristic::suggested(diagonal, cost_model) as usize;
+        let highlighted = if cursor_mem.get(DIAGONAL_CURSOR_ID) == Some(&(diagonal as usize)) {
+            *cursor_mem.get(CURSOR_ID).unwrap_or(&suggested)
+        } else {
+            suggested
+        };
+        let combination = *cursor_mem.get(COMBINATION_CURSOR_ID).unwrap_or(&0);
         if params.algo.supported_heuristics() {
             return HeuristicPicker {
                 params,
                 selected: None,
                 highlighted,
+                layout: ListLayout::default(),
+                combination,
             };
         } else {
             return HeuristicPicker {
                 params,
                 selected: Some(0),
                 highlighted,
+                layout: ListLayout::default(),
+                combination,
             };
         }
     }
 }
 
+impl HeuristicPicker {
+    fn end_count(&self) -> usize {
+        self.params.map.variants[self.params.variant].ends.len()
+    }
+}
+
 impl Scene for HeuristicPicker {
     fn update(&mut self, _ctx: &mut Context) -> Result<(), GameError> {
         Ok(())
@@ -64,16 +97,38 @@ impl Scene for HeuristicPicker {
         let text_offset = renderer.calc_percent_to_point(0.19, 0.14);
         let text_spacing = renderer.calc_height(0.05);
 
+        let admissible_x = text_offset.x + renderer.calc_width(0.3);
+        let suggested_x = text_offset.x + renderer.calc_width(0.5);
+        let suggested = Heuristic::suggested(self.params.diagonal, self.params.cost_model);
+
         let list_mesh = renderer.make_list_indicator_mesh(ctx, renderer.calc_height(0.03))?;
+        self.layout = ListLayout::new(text_offset.x - text_spacing, text_offset.y, renderer.calc_width(0.6), text_spacing, Heuristic::len());
 
         renderer.draw_white_text(ctx, "Choose a heuristic", title_offset, renderer.calc_height(0.04), false);
 
+        let end_count = self.end_count();
+        let combination = Combination::from_index(self.combination);
         for i in 0..Heuristic::len() {
-            renderer.draw_white_text(ctx, Heuristic::from_index(i).name(), point(text_offset.x, text_offset.y + (text_spacing * i as f32)), renderer.calc_height(0.04), false);
+            let heuristic = Heuristic::from_index(i);
+            let y = text_offset.y + (text_spacing * i as f32);
+            renderer.draw_white_text(ctx, heuristic.name(), point(text_offset.x, y), renderer.calc_height(0.04), false);
+            if heuristic.is_admissible(self.params.diagonal, self.params.cost_model, combination, end_count) {
+                renderer.draw_text(ctx, "admissible", point(admissible_x, y), (0.5, 1., 0.5, 1.).into(), renderer.calc_height(0.04), false);
+            } else {
+                renderer.draw_text(ctx, "may overestimate", point(admissible_x, y), (0.5, 0.5, 0.5, 1.).into(), renderer.calc_height(0.04), false);
+            }
+            if heuristic == suggested {
+                renderer.draw_text(ctx, "suggested", point(suggested_x, y), (1., 1., 0.5, 1.).into(), renderer.calc_height(0.04), false);
+            }
         }
 
         renderer.draw_mesh(ctx, list_mesh.as_ref(), point(text_offset.x - text_spacing, text_offset.y + renderer.calc_height(0.008) + (self.highlighted as f32 * text_spacing)));
 
+        if end_count > 1 {
+            let combination_offset = point(text_offset.x, text_offset.y + (text_spacing * (Heuristic::len() as f32 + 1.)));
+            renderer.draw_white_text(ctx, format!("Combine {} ends using: < {} >", end_count, combination.name()), combination_offset, renderer.calc_height(0.04), false);
+        }
+
         Ok(())
     }
 
@@ -89,6 +144,16 @@ impl Scene for HeuristicPicker {
                     self.highlighted += 1;
                 }
             }
+            KeyCode::Left => {
+                if self.combination > 0 {
+                    self.combination -= 1;
+                }
+            }
+            KeyCode::Right => {
+                if self.combination < Combination::len() - 1 {
+                    self.combination += 1;
+                }
+            }
             _ => {}
         }
     }
@@ -100,33 +165,68 @@ impl Scene for HeuristicPicker {
         }
     }
 
+    fn on_mouse_click(&mut self, position: DPPoint, button: MouseButton) {
+        if button != MouseButton::Left {
+            return;
+        }
+        if let Some(row) = self.layout.row_at(position) {
+            self.highlighted = row;
+            self.selected = Some(row);
+        }
+    }
+
+    fn on_mouse_scroll(&mut self, amount: f32) {
+        if amount > 0. {
+            self.on_button_down(KeyCode::Up);
+        } else if amount < 0. {
+            self.on_button_down(KeyCode::Down);
+        }
+    }
+
     fn is_complete(&self) -> bool {
         return self.selected.is_some();
     }
 
     fn get_next_stage_params(&self, cursor_mem: &mut HashMap<&str, usize>) -> SceneParams {
         cursor_mem.insert(CURSOR_ID, self.highlighted);
+        cursor_mem.insert(COMBINATION_CURSOR_ID, self.combination);
+        cursor_mem.insert(DIAGONAL_CURSOR_ID, self.params.diagonal as usize);
+        let combination = Combination::from_index(self.combination);
         let map_clone = self.params.map.clone();
         let columns = map_clone.get_column_count() as i32;
         let rows = map_clone.get_row_count() as i32;
         let heuristic = Heuristic::from_index(self.selected.expect("Nothing selected"));
+        let costs: SharedCost = Rc::new(RefCell::new(map_clone.cost.clone()));
+        let costs_clone = costs.clone();
         let cost_calc = Box::new(move |xy: Coord| {
             if xy.is_out_of_bounds(columns, rows) {
                 -1
             } else {
-                map_clone.cost[xy.x as usize][xy.y as usize]
+                costs_clone.borrow()[xy.x as usize][xy.y as usize]
             }
         });
         let algo: Box<dyn Algorithm> = match self.params.algo {
-            Algo::AStar => Box::new(Astar::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal, heuristic)),
-            Algo::Dijkstra => Box::new(Dijkstra::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal))
+            Algo::AStar => Box::new(Astar::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal, self.params.cost_model, heuristic, combination)),
+            Algo::Dijkstra => Box::new(Dijkstra::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal, self.params.cost_model)),
+            Algo::Bfs => Box::new(Bfs::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal)),
+            Algo::Greedy => Box::new(Greedy::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal, heuristic, combination)),
+            Algo::Jps => Box::new(Jps::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal, self.params.cost_model, heuristic, combination)),
+            Algo::Theta => Box::new(Theta::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal, self.params.cost_model, heuristic, combination)),
+            Algo::Bidirectional => Box::new(Bidirectional::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal, self.params.cost_model, heuristic, combination)),
+            Algo::DStarLite => Box::new(DStarLite::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal, self.params.cost_model, heuristic)),
+            Algo::WeightedAStar => Box::new(Astar::new_weighted(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal, self.params.cost_model, heuristic, combination, DEFAULT_WEIGHT)),
+            Algo::IdaStar => Box::new(IdaStar::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal, self.params.cost_model, heuristic, combination)),
+            Algo::Tour => Box::new(Tour::new_fixed_target(self.params.map.variants[self.params.variant].start, self.params.map.variants[self.params.variant].ends.clone(), cost_calc, columns, rows, self.params.diagonal, self.params.cost_model)),
         };
         SceneParams::AlgoRunner {
             map: self.params.map.clone(),
+            costs,
             heuristic,
+            combination,
             algo: Rc::new(RefCell::new(algo)),
             algo_name: self.params.algo.name(),
             diagonal: self.params.diagonal,
+            cost_model: self.params.cost_model,
             variant: self.params.variant,
         }
     }
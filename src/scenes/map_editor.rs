@@ -0,0 +1,275 @@
+use crate::{point, DPPoint};
+use crate::data::Coord;
+use crate::data::maps::{Map, Variant, NODE_FREE, NODE_WALL, write_map, write_map_file};
+use crate::scenes::{Scene, SceneParams};
+use crate::scenes::map_picker::{MAP_CURSOR_ID, VARIANT_CURSOR_ID};
+use crate::graphics::renderer::Renderer;
+use crate::graphics::map_rendering::{draw_map_with_costs_start_end, fit_cell_size};
+use ggez::{Context, GameError};
+use ggez::event::{KeyCode, MouseButton};
+use std::rc::Rc;
+use std::collections::HashMap;
+
+const BRUSH_CURSOR_ID: &'static str = "editor_brush";
+//Costs a tile can be painted with, a wall is saved as 9
+const MAX_TILE_COST: i32 = 8;
+
+pub struct MapEditor {
+    which: usize,
+    map: Map,
+    //the map as it was last loaded or saved, to tell if there are unsaved edits
+    saved_map: Map,
+    //key that was pressed once to leave with unsaved edits, pressing it again leaves
+    leave_requested: Option<KeyCode>,
+    cursor: Coord,
+    //cost painted onto tiles, NODE_WALL for walls
+    brush: i32,
+    //which variant's start and ends are shown and edited
+    variant: usize,
+    //where the map was last drawn, so the mouse can be matched to a tile
+    grid: ((f32, f32), f32),
+    save_requested: bool,
+    saved: bool,
+    message: Option<String>,
+}
+
+impl MapEditor {
+    pub fn new(map: Rc<Map>, which: usize, cursor_mem: &HashMap<&str, usize>) -> MapEditor {
+        let brush = match cursor_mem.get(BRUSH_CURSOR_ID) {
+            Some(&brush) if brush as i32 <= MAX_TILE_COST => brush as i32,
+            Some(_) => NODE_WALL,
+            None => NODE_FREE,
+        };
+        MapEditor {
+            which,
+            cursor: map.variants[0].start,
+            map: map.as_ref().clone(),
+            saved_map: map.as_ref().clone(),
+            leave_requested: None,
+            brush,
+            variant: 0,
+            grid: ((0., 0.), 1.),
+            save_requested: false,
+            saved: false,
+            message: None,
+        }
+    }
+}
+
+impl MapEditor {
+    fn move_cursor(&mut self, x: i32, y: i32) {
+        let new_cursor = self.cursor + (x, y);
+        if !new_cursor.is_out_of_bounds(self.map.get_column_count() as i32, self.map.get_row_count() as i32) {
+            self.cursor = new_cursor;
+        }
+    }
+
+    fn is_start_or_end(&self, xy: Coord) -> bool {
+        self.map.variants.iter().any(|variant| variant.start == xy || variant.ends.contains(&xy))
+    }
+
+    fn paint(&mut self, xy: Coord) {
+        if self.brush == NODE_WALL && self.is_start_or_end(xy) {
+            self.message = Some(format!("Starts and ends can't be walls, {} not painted", xy));
+            return;
+        }
+        self.map.cost[xy.x as usize][xy.y as usize] = self.brush;
+    }
+
+    fn change_brush(&mut self, change: i32) {
+        //walls come after the highest cost
+        let index = if self.brush == NODE_WALL { MAX_TILE_COST + 1 } else { self.brush };
+        let index = (index + change).clamp(0, MAX_TILE_COST + 1);
+        self.brush = if index > MAX_TILE_COST { NODE_WALL } else { index };
+    }
+
+    fn is_wall(&self, xy: Coord) -> bool {
+        self.map.cost[xy.x as usize][xy.y as usize] == NODE_WALL
+    }
+
+    fn place_start(&mut self) {
+        if self.is_wall(self.cursor) {
+            self.message = Some(String::from("Starts can't be placed in walls"));
+            return;
+        }
+        let cursor = self.cursor;
+        let variant = &mut self.map.variants[self.variant];
+        variant.ends.retain(|&end| end != cursor);
+        variant.start = cursor;
+    }
+
+    fn toggle_end(&mut self) {
+        if self.is_wall(self.cursor) {
+            self.message = Some(String::from("Ends can't be placed in walls"));
+            return;
+        }
+        let cursor = self.cursor;
+        let variant = &mut self.map.variants[self.variant];
+        if variant.start == cursor {
+            return;
+        }
+        if variant.ends.contains(&cursor) {
+            variant.ends.retain(|&end| end != cursor);
+        } else {
+            variant.ends.push(cursor);
+        }
+    }
+
+    fn add_variant(&mut self) {
+        if self.is_wall(self.cursor) {
+            self.message = Some(String::from("Starts can't be placed in walls"));
+            return;
+        }
+        self.map.variants.push(Variant { start: self.cursor, ends: vec![] });
+        self.variant = self.map.variants.len() - 1;
+    }
+
+    fn delete_variant(&mut self) {
+        if self.map.variants.len() == 1 {
+            self.message = Some(String::from("A map needs at least one variant"));
+            return;
+        }
+        self.map.variants.remove(self.variant);
+        self.variant = self.variant.min(self.map.variants.len() - 1);
+    }
+
+    fn tile_at(&self, position: DPPoint) -> Option<Coord> {
+        let ((x, y), cell_size) = self.grid;
+        let column = ((position.x - x) / cell_size).floor();
+        let row = ((position.y - y) / cell_size).floor();
+        let xy = Coord::new(column as i32, row as i32);
+        if column < 0. || row < 0. || xy.is_out_of_bounds(self.map.get_column_count() as i32, self.map.get_row_count() as i32) {
+            return None;
+        }
+        return Some(xy);
+    }
+
+    fn save(&mut self, ctx: &mut Context) {
+        if let Some(idx) = self.map.variants.iter().position(|variant| variant.ends.is_empty()) {
+            self.message = Some(format!("Variant {} has no ends, add one with E before saving", idx + 1));
+            return;
+        }
+        match write_map_file(ctx, self.which, &write_map(&self.map)) {
+            Ok(_) => {
+                self.saved_map = self.map.clone();
+                self.saved = true;
+            }
+            Err(err) => self.message = Some(format!("Failed to save: {}", err)),
+        }
+    }
+}
+
+impl Scene for MapEditor {
+    fn update(&mut self, ctx: &mut Context) -> Result<(), GameError> {
+        if self.save_requested {
+            self.save_requested = false;
+            self.save(ctx);
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, ctx: &mut Context, renderer: &mut Renderer) -> Result<(), GameError> {
+        let cell_size = fit_cell_size(&self.map, renderer.calc_percent_to_px(0.96, 0.84));
+        let grid_width = cell_size * (self.map.get_column_count() as f32);
+        let grid_height = cell_size * (self.map.get_row_count() as f32);
+        let x = renderer.calc_width(0.5) - (grid_width * 0.5);
+        let y = renderer.calc_height(0.5) - (grid_height * 0.5) + renderer.calc_height(0.02);
+        self.grid = ((x, y), cell_size);
+
+        draw_map_with_costs_start_end(ctx, renderer, (x, y), cell_size, &self.map, self.variant)?;
+
+        let cursor_mesh = renderer.make_square_mesh(ctx, cell_size, false, 3.)?;
+        renderer.draw_coloured_mesh(ctx, cursor_mesh.as_ref(), point(x + (self.cursor.x as f32 * cell_size), y + (self.cursor.y as f32 * cell_size)), (1., 1., 0., 1.).into());
+
+        let brush = if self.brush == NODE_WALL { String::from("wall") } else { format!("cost {}", self.brush) };
+        let variant = &self.map.variants[self.variant];
+        let display = format!("Editing map {}  |  Brush: {}  |  Variant {} of {}, {} ends  |  Cursor {}", self.which, brush, self.variant + 1, self.map.variants.len(), variant.ends.len(), self.cursor);
+        renderer.draw_white_text(ctx, display, point(8., 4.), renderer.calc_height(0.04), false);
+
+        let help = "0-8 brush cost, 9 walls, Space/click paint  |  S start, E end  |  Tab next variant, N add, Delete remove  |  Return save";
+        renderer.draw_white_text(ctx, help, point(8., y + grid_height + renderer.calc_height(0.02)), renderer.calc_height(0.03), false);
+
+        if let Some(message) = &self.message {
+            renderer.draw_text(ctx, message.clone(), point(8., y + grid_height + renderer.calc_height(0.06)), (1., 0.5, 0.5, 1.).into(), renderer.calc_height(0.03), false);
+        }
+
+        Ok(())
+    }
+
+    fn on_button_down(&mut self, keycode: KeyCode) {
+        self.message = None;
+        self.leave_requested = None;
+        match keycode {
+            KeyCode::Up => self.move_cursor(0, -1),
+            KeyCode::Down => self.move_cursor(0, 1),
+            KeyCode::Left => self.move_cursor(-1, 0),
+            KeyCode::Right => self.move_cursor(1, 0),
+            KeyCode::Space => self.paint(self.cursor),
+            KeyCode::Key0 => self.brush = 0,
+            KeyCode::Key1 => self.brush = 1,
+            KeyCode::Key2 => self.brush = 2,
+            KeyCode::Key3 => self.brush = 3,
+            KeyCode::Key4 => self.brush = 4,
+            KeyCode::Key5 => self.brush = 5,
+            KeyCode::Key6 => self.brush = 6,
+            KeyCode::Key7 => self.brush = 7,
+            KeyCode::Key8 => self.brush = 8,
+            KeyCode::Key9 => self.brush = NODE_WALL,
+            KeyCode::S => self.place_start(),
+            KeyCode::E => self.toggle_end(),
+            KeyCode::Tab => self.variant = (self.variant + 1) % self.map.variants.len(),
+            KeyCode::N => self.add_variant(),
+            KeyCode::Delete | KeyCode::Back => self.delete_variant(),
+            _ => {}
+        }
+    }
+
+    fn on_button_up(&mut self, keycode: KeyCode) {
+        match keycode {
+            KeyCode::Return => self.save_requested = true,
+            _ => {}
+        }
+    }
+
+    //Left click paints the tile, right click only moves the cursor to it
+    fn on_mouse_click(&mut self, position: DPPoint, button: MouseButton) {
+        if let Some(xy) = self.tile_at(position) {
+            self.message = None;
+            self.leave_requested = None;
+            self.cursor = xy;
+            if button == MouseButton::Left {
+                self.paint(xy);
+            }
+        }
+    }
+
+    fn on_mouse_scroll(&mut self, amount: f32) {
+        if amount > 0. {
+            self.change_brush(1);
+        } else if amount < 0. {
+            self.change_brush(-1);
+        }
+    }
+
+    fn intercepts_key(&mut self, keycode: KeyCode) -> bool {
+        if !matches!(keycode, KeyCode::R | KeyCode::Q | KeyCode::Escape) || self.map == self.saved_map || self.leave_requested == Some(keycode) {
+            return false;
+        }
+        self.leave_requested = Some(keycode);
+        self.message = Some(format!("Map {} has unsaved edits, press {:?} again to discard them or Return to save", self.which, keycode));
+        return true;
+    }
+
+    fn is_complete(&self) -> bool {
+        return self.saved;
+    }
+
+    fn get_next_stage_params(&self, cursor_mem: &mut HashMap<&str, usize>) -> SceneParams {
+        let brush = if self.brush == NODE_WALL { MAX_TILE_COST + 1 } else { self.brush };
+        cursor_mem.insert(BRUSH_CURSOR_ID, brush as usize);
+        //the picker's variant cursor may point at a variant that was deleted
+        cursor_mem.insert(MAP_CURSOR_ID, self.which);
+        cursor_mem.insert(VARIANT_CURSOR_ID, self.variant);
+        return SceneParams::MapSelection;
+    }
+}
@@ -1,20 +1,34 @@
-use crate::{Scene, point, GRID_HORZ_COUNT, GRID_VERT_COUNT, SceneParams};
+use crate::{Scene, point, DPPoint, SceneParams};
 use ggez::{Context, GameError};
-use ggez::event::KeyCode;
-use crate::data::maps::{Map, read_map_file};
+use ggez::event::{KeyCode, MouseButton};
+use ggez::graphics::Rect;
+use crate::data::maps::{Map, MapError, read_map_file};
 use crate::graphics::renderer::*;
 use std::rc::Rc;
-use crate::graphics::map_rendering::{draw_map_with_costs, draw_map_with_costs_start_end};
+use crate::graphics::map_rendering::{draw_map_with_costs, draw_map_with_costs_start_end, draw_map_error, fit_cell_size};
 use std::collections::HashMap;
 
-const MAP_CURSOR_ID: &'static str = "map_highlighted";
-const VARIANT_CURSOR_ID: &'static str = "variant_highlighted";
+pub const MAP_CURSOR_ID: &'static str = "map_highlighted";
+pub const VARIANT_CURSOR_ID: &'static str = "variant_highlighted";
+//Maps are shown in slots this many tiles across and down, other sizes are scaled to fit
+const SLOT_TILES: (f32, f32) = (32., 17.);
 
 pub struct MapPicker {
-    maps: Vec<Rc<Map>>,
+    //maps that couldn't be loaded are shown as their error instead
+    maps: Vec<Result<Rc<Map>, MapError>>,
     selected: Option<usize>,
+    //the highlighted map is opened in the editor instead of being run
+    editing: bool,
     highlighted: usize,
     variant_highlighted: usize,
+    //where each map and variant was last drawn, so they can be clicked
+    targets: Vec<(Rect, Target)>,
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    Map(usize),
+    Variant(usize),
 }
 
 impl MapPicker {
@@ -22,21 +36,47 @@ impl MapPicker {
         MapPicker {
             maps: vec![],
             selected: None,
+            editing: false,
             highlighted: *cursor_mem.get(MAP_CURSOR_ID).unwrap_or(&0),
             variant_highlighted: *cursor_mem.get(VARIANT_CURSOR_ID).unwrap_or(&0),
+            targets: vec![],
         }
     }
 }
 
 impl MapPicker {
     pub fn setup(&mut self, ctx: &mut Context, _renderer: &mut Renderer) -> Result<(), GameError> {
-        for i in 0..10 {
-            self.maps.push(Rc::new(read_map_file(ctx, i)));
-        }
+        let maps = (0..10).map(|i| read_map_file(ctx, i).map(Rc::new)).collect();
+        self.set_maps(maps);
 
         Ok(())
     }
 
+    //The remembered cursors may point past maps or variants that have since been edited away
+    pub fn set_maps(&mut self, maps: Vec<Result<Rc<Map>, MapError>>) {
+        self.maps = maps;
+        self.highlighted = self.highlighted.min(self.maps.len().saturating_sub(1));
+        self.variant_highlighted = self.variant_highlighted.min(self.variant_count().saturating_sub(1));
+    }
+
+    fn highlighted_map(&self) -> Option<Rc<Map>> {
+        self.maps[self.highlighted].as_ref().ok().cloned()
+    }
+
+    fn variant_count(&self) -> usize {
+        self.highlighted_map().map(|map| map.variants.len()).unwrap_or(0)
+    }
+
+    fn draw_map(&self, ctx: &mut Context, renderer: &mut Renderer, offset: (f32, f32), slot_size: (f32, f32), which: usize) -> Result<(), GameError> {
+        match &self.maps[which] {
+            Ok(map) => {
+                let (offset, cell_size) = fit_in_slot(map, offset, slot_size);
+                draw_map_with_costs(ctx, renderer, offset, cell_size, map.as_ref())
+            }
+            Err(err) => draw_map_error(ctx, renderer, offset, slot_size, err),
+        }
+    }
+
     fn get_cell_size_for_screen(size: (f32, f32)) -> f32 {
         let cell_w = match size.0 {
             res if res >= 3840. => 24.,
@@ -58,6 +98,18 @@ impl MapPicker {
     }
 }
 
+//Where to draw `map` and how big its tiles are so it's centered in the slot at `offset`
+fn fit_in_slot(map: &Map, offset: (f32, f32), slot_size: (f32, f32)) -> ((f32, f32), f32) {
+    let cell_size = fit_cell_size(map, slot_size);
+    let map_size = (cell_size * map.get_column_count() as f32, cell_size * map.get_row_count() as f32);
+    return ((offset.0 + (slot_size.0 - map_size.0) * 0.5, offset.1 + (slot_size.1 - map_size.1) * 0.5), cell_size);
+}
+
+fn draw_variant(ctx: &mut Context, renderer: &mut Renderer, map: &Map, offset: (f32, f32), slot_size: (f32, f32), variant: usize) -> Result<(), GameError> {
+    let (offset, cell_size) = fit_in_slot(map, offset, slot_size);
+    draw_map_with_costs_start_end(ctx, renderer, offset, cell_size, map, variant)
+}
+
 impl Scene for MapPicker {
     fn update(&mut self, _ctx: &mut Context) -> Result<(), GameError> {
         Ok(())
@@ -66,7 +118,7 @@ impl Scene for MapPicker {
     fn render(&mut self, ctx: &mut Context, renderer: &mut Renderer) -> Result<(), GameError> {
         let screen_size = Renderer::get_screen_size(ctx);
         let cell_size = MapPicker::get_cell_size_for_screen(screen_size);
-        let grid_size = (cell_size * GRID_HORZ_COUNT as f32, cell_size * GRID_VERT_COUNT as f32);
+        let grid_size = (cell_size * SLOT_TILES.0, cell_size * SLOT_TILES.1);
         let grid_spacing = screen_size.1 * 0.05;
         let indicator_size = renderer.calc_height(0.03);
         let indicator_spacing = renderer.calc_height(0.03);
@@ -76,35 +128,59 @@ impl Scene for MapPicker {
         let indicator_pos = (grid_spacing + grid_size.0 + indicator_spacing, (screen_size.1 * 0.5) - (indicator_size * 0.5));
         let variant_offset = (indicator_pos.0 + indicator_size + indicator_spacing + grid_size.0 * 0.5 + variant_spacing, (screen_size.1 * 0.5) - (grid_size.1 * 0.5));
 
-        if self.variant_highlighted > 0 {
-            draw_map_with_costs_start_end(ctx, renderer, (variant_offset.0 - grid_size.0 - variant_spacing, variant_offset.1), cell_size, self.maps[self.highlighted].as_ref(), self.variant_highlighted - 1)?;
+        //the map column is drawn over the variants, so it's checked first
+        let grid_background_width = indicator_pos.0 + indicator_spacing * 2.;
+        self.targets.clear();
+        for i in -2..=2 {
+            let map = self.highlighted as i32 + i;
+            if map >= 0 && map < self.maps.len() as i32 {
+                let y = grid_offset.1 + (grid_spacing + grid_size.1) * i as f32;
+                self.targets.push((Rect::new(grid_offset.0, y, grid_size.0, grid_size.1), Target::Map(map as usize)));
+            }
         }
+        let variant_count = self.variant_count() as i32;
+        for i in -1..=2 {
+            let variant = self.variant_highlighted as i32 + i;
+            let x = variant_offset.0 + (grid_size.0 + variant_spacing) * i as f32;
+            if variant >= 0 && variant < variant_count && x >= grid_background_width {
+                self.targets.push((Rect::new(x, variant_offset.1, grid_size.0, grid_size.1), Target::Variant(variant as usize)));
+            }
+        }
+
+        match &self.maps[self.highlighted] {
+            Ok(map) => {
+                if self.variant_highlighted > 0 {
+                    draw_variant(ctx, renderer, map, (variant_offset.0 - grid_size.0 - variant_spacing, variant_offset.1), grid_size, self.variant_highlighted - 1)?;
+                }
 
-        draw_map_with_costs_start_end(ctx, renderer, variant_offset, cell_size, self.maps[self.highlighted].as_ref(), self.variant_highlighted)?;
+                draw_variant(ctx, renderer, map, variant_offset, grid_size, self.variant_highlighted)?;
 
-        if self.variant_highlighted < (self.maps[self.highlighted].variants.len() - 1) {
-            draw_map_with_costs_start_end(ctx, renderer, (variant_offset.0 + grid_size.0 + variant_spacing, variant_offset.1), cell_size, self.maps[self.highlighted].as_ref(), self.variant_highlighted + 1)?;
-            if self.variant_highlighted < (self.maps[self.highlighted].variants.len() - 2) {
-                draw_map_with_costs_start_end(ctx, renderer, (variant_offset.0 + (grid_size.0 + variant_spacing) * 2., variant_offset.1), cell_size, self.maps[self.highlighted].as_ref(), self.variant_highlighted + 2)?;
+                if self.variant_highlighted < (map.variants.len() - 1) {
+                    draw_variant(ctx, renderer, map, (variant_offset.0 + grid_size.0 + variant_spacing, variant_offset.1), grid_size, self.variant_highlighted + 1)?;
+                    if self.variant_highlighted < (map.variants.len() - 2) {
+                        draw_variant(ctx, renderer, map, (variant_offset.0 + (grid_size.0 + variant_spacing) * 2., variant_offset.1), grid_size, self.variant_highlighted + 2)?;
+                    }
+                }
             }
+            Err(err) => draw_map_error(ctx, renderer, variant_offset, grid_size, err)?,
         }
 
-        let grid_background = renderer.make_rect_mesh(ctx, indicator_pos.0 + indicator_spacing * 2., screen_size.1, true, 0.)?;
+        let grid_background = renderer.make_rect_mesh(ctx, grid_background_width, screen_size.1, true, 0.)?;
         renderer.draw_coloured_mesh(ctx, grid_background.as_ref(), point(0., 0.), (0, 0, 0, 255).into());
 
         if self.highlighted > 0 {
-            draw_map_with_costs(ctx, renderer, (grid_offset.0, grid_offset.1 - grid_spacing - grid_size.1), cell_size, self.maps[self.highlighted - 1].as_ref())?;
+            self.draw_map(ctx, renderer, (grid_offset.0, grid_offset.1 - grid_spacing - grid_size.1), grid_size, self.highlighted - 1)?;
             if self.highlighted > 1 {
-                draw_map_with_costs(ctx, renderer, (grid_offset.0, grid_offset.1 - ((grid_spacing + grid_size.1) * 2.)), cell_size, self.maps[self.highlighted - 2].as_ref())?;
+                self.draw_map(ctx, renderer, (grid_offset.0, grid_offset.1 - ((grid_spacing + grid_size.1) * 2.)), grid_size, self.highlighted - 2)?;
             }
         }
 
-        draw_map_with_costs(ctx, renderer, grid_offset, cell_size, self.maps[self.highlighted].as_ref())?;
+        self.draw_map(ctx, renderer, grid_offset, grid_size, self.highlighted)?;
 
         if self.highlighted < (self.maps.len() - 1) {
-            draw_map_with_costs(ctx, renderer, (grid_offset.0, grid_offset.1 + grid_spacing + grid_size.1), cell_size, self.maps[self.highlighted + 1].as_ref())?;
+            self.draw_map(ctx, renderer, (grid_offset.0, grid_offset.1 + grid_spacing + grid_size.1), grid_size, self.highlighted + 1)?;
             if self.highlighted < (self.maps.len() - 2) {
-                draw_map_with_costs(ctx, renderer, (grid_offset.0, grid_offset.1 + (grid_spacing + grid_size.1) * 2.), cell_size, self.maps[self.highlighted + 2].as_ref())?;
+                self.draw_map(ctx, renderer, (grid_offset.0, grid_offset.1 + (grid_spacing + grid_size.1) * 2.), grid_size, self.highlighted + 2)?;
             }
         }
 
@@ -121,6 +197,7 @@ impl Scene for MapPicker {
         renderer.draw_coloured_mesh(ctx, grid_shader.as_ref(), point(grid_offset.0 - 10., screen_size.1 - grid_size.1 * 0.8), (0., 0., 0., 0.75).into());
 
         renderer.draw_white_text(ctx, String::from("Choose map and variant"), point(screen_size.0 / 2., 50.), renderer.calc_height(0.04), true);
+        renderer.draw_white_text(ctx, String::from("E to edit the highlighted map"), point(screen_size.0 / 2., screen_size.1 - 50.), renderer.calc_height(0.03), true);
 
         Ok(())
     }
@@ -134,7 +211,7 @@ impl Scene for MapPicker {
                 }
             }
             KeyCode::Down => {
-                if self.highlighted < 9 {
+                if self.highlighted + 1 < self.maps.len() {
                     self.highlighted += 1;
                     self.variant_highlighted = 0;
                 }
@@ -145,7 +222,7 @@ impl Scene for MapPicker {
                 }
             }
             KeyCode::Right => {
-                if self.variant_highlighted < self.maps[self.highlighted].variants.len() - 1 {
+                if self.variant_highlighted + 1 < self.variant_count() {
                     self.variant_highlighted += 1;
                 }
             }
@@ -155,22 +232,59 @@ impl Scene for MapPicker {
 
     fn on_button_up(&mut self, keycode: KeyCode) {
         match keycode {
-            KeyCode::Return => {
-                self.selected = Some(self.highlighted);
+            KeyCode::Return if self.maps[self.highlighted].is_ok() => self.selected = Some(self.highlighted),
+            KeyCode::E => self.editing = self.maps[self.highlighted].is_ok(),
+            _ => {}
+        }
+    }
+
+    //Clicking a map or variant highlights it, clicking the highlighted variant selects it
+    fn on_mouse_click(&mut self, position: DPPoint, button: MouseButton) {
+        if button != MouseButton::Left {
+            return;
+        }
+        let target = self.targets.iter()
+            .find(|(rect, _)| rect.contains(position))
+            .map(|(_, target)| *target);
+        match target {
+            Some(Target::Map(map)) if map != self.highlighted => {
+                self.highlighted = map;
+                self.variant_highlighted = 0;
+            }
+            Some(Target::Variant(variant)) => {
+                if variant == self.variant_highlighted {
+                    self.selected = Some(self.highlighted);
+                } else {
+                    self.variant_highlighted = variant;
+                }
             }
             _ => {}
         }
     }
 
+    fn on_mouse_scroll(&mut self, amount: f32) {
+        if amount > 0. {
+            self.on_button_down(KeyCode::Up);
+        } else if amount < 0. {
+            self.on_button_down(KeyCode::Down);
+        }
+    }
+
     fn is_complete(&self) -> bool {
-        return self.selected.is_some();
+        return self.selected.is_some() || self.editing;
     }
 
     fn get_next_stage_params(&self, cursor_mem: &mut HashMap<&str, usize>) -> SceneParams {
         cursor_mem.insert(MAP_CURSOR_ID, self.highlighted);
         cursor_mem.insert(VARIANT_CURSOR_ID, self.variant_highlighted);
+        if self.editing {
+            return SceneParams::MapEditing {
+                map: self.highlighted_map().expect("Broken maps can't be edited"),
+                which: self.highlighted,
+            };
+        }
         return SceneParams::AlgoSelection {
-            map: self.maps[self.selected.unwrap()].clone(),
+            map: self.highlighted_map().expect("Broken maps can't be selected"),
             variant: self.variant_highlighted,
         };
     }
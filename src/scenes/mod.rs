@@ -1,32 +1,76 @@
 pub mod algo_picker;
+pub mod cost_model_picker;
 pub mod diagonal_picker;
 pub mod executor;
 pub mod heuristic_picker;
+pub mod map_editor;
 pub mod map_picker;
 
+#[cfg(test)]
+mod tests;
+
 use crate::graphics::renderer::Renderer;
 use ggez::{Context, GameResult};
-use ggez::event::KeyCode;
+use ggez::event::{KeyCode, MouseButton};
 use std::rc::Rc;
 use std::cell::RefCell;
-use crate::data::{maps::Map, diagonal::Diagonal, heuristic::Heuristic};
+use crate::data::{maps::{Map, SharedCost}, diagonal::Diagonal, cost_model::CostModel, heuristic::{Heuristic, Combination}};
 use crate::algos::{Algo, Algorithm};
 use std::collections::HashMap;
+use crate::DPPoint;
 
 pub trait Scene {
     fn update(&mut self, ctx: &mut Context) -> GameResult<()>;
     fn render(&mut self, ctx: &mut Context, renderer: &mut Renderer) -> GameResult<()>;
     fn on_button_down(&mut self, keycode: KeyCode);
     fn on_button_up(&mut self, keycode: KeyCode);
+    //Called before keys every scene shares (R restart, Q and Escape quit) are handled, true stops them
+    fn intercepts_key(&mut self, _keycode: KeyCode) -> bool {
+        false
+    }
+    //`position` is in screen pixels
+    fn on_mouse_move(&mut self, _position: DPPoint) {}
+    fn on_mouse_down(&mut self, _position: DPPoint, _button: MouseButton) {}
+    //Called when the button is let go
+    fn on_mouse_click(&mut self, _position: DPPoint, _button: MouseButton) {}
+    //`amount` is positive when scrolling up
+    fn on_mouse_scroll(&mut self, _amount: f32) {}
     fn is_complete(&self) -> bool;
     fn get_next_stage_params(&self, cursor_mem: &mut HashMap<&str, usize>) -> SceneParams;
 }
 
 
+//Where a list of options was last drawn, so the mouse can be matched to a row
+#[derive(Clone, Copy, Default)]
+pub struct ListLayout {
+    x: f32,
+    y: f32,
+    width: f32,
+    row_height: f32,
+    count: usize,
+}
+
+impl ListLayout {
+    pub fn new(x: f32, y: f32, width: f32, row_height: f32, count: usize) -> ListLayout {
+        ListLayout { x, y, width, row_height, count }
+    }
+
+    pub fn row_at(&self, position: DPPoint) -> Option<usize> {
+        if position.x < self.x || position.x > self.x + self.width || position.y < self.y || self.row_height <= 0. {
+            return None;
+        }
+        let row = ((position.y - self.y) / self.row_height) as usize;
+        return if row < self.count { Some(row) } else { None };
+    }
+}
+
 pub enum SceneParams {
+    MapSelection,
+    MapEditing { map: Rc<Map>, which: usize },
     AlgoSelection { map: Rc<Map>, variant: usize },
     DiagonalSelection { map: Rc<Map>, algo: Algo, variant: usize },
-    HeuristicSelection { map: Rc<Map>, algo: Algo, diagonal: Diagonal, variant: usize },
-    AlgoRunner { map: Rc<Map>, algo: Rc<RefCell<Box<dyn Algorithm>>>, algo_name: String, diagonal: Diagonal, heuristic: Heuristic, variant: usize },
+    CostModelSelection { map: Rc<Map>, algo: Algo, diagonal: Diagonal, variant: usize },
+    HeuristicSelection { map: Rc<Map>, algo: Algo, diagonal: Diagonal, cost_model: CostModel, variant: usize },
+    AlgoRunner { map: Rc<Map>, costs: SharedCost, algo: Rc<RefCell<Box<dyn Algorithm>>>, algo_name: String, diagonal: Diagonal, cost_model: CostModel, heuristic: Heuristic, combination: Combination, variant: usize },
     EndOfProgram
 }
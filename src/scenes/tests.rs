@@ -0,0 +1,72 @@
+use std::rc::Rc;
+use std::collections::HashMap;
+use ggez::event::KeyCode;
+use crate::data::maps::parse_map;
+use crate::scenes::{Scene, SceneParams};
+use crate::scenes::map_editor::MapEditor;
+use crate::scenes::map_picker::{MapPicker, MAP_CURSOR_ID, VARIANT_CURSOR_ID};
+use crate::scenes::algo_picker::AlgoPicker;
+use crate::algos::Algo;
+
+#[test]
+fn picker_survives_the_highlighted_variant_being_deleted() {
+    let map = Rc::new(parse_map("M0000\nM0000\nS0,0\nE3,1\nS1,0\nE3,0\n", "map0").unwrap_or_else(|err| panic!("{}", err)));
+    let mut cursor_mem = HashMap::new();
+
+    let mut editor = MapEditor::new(map, 0, &cursor_mem);
+    editor.on_button_down(KeyCode::Tab);
+    editor.on_button_down(KeyCode::Delete);
+    assert!(matches!(editor.get_next_stage_params(&mut cursor_mem), SceneParams::MapSelection));
+
+    let edited = parse_map("M0000\nM0000\nS0,0\nE3,1\n", "map0").unwrap_or_else(|err| panic!("{}", err));
+    let mut picker = MapPicker::new(&cursor_mem);
+    picker.set_maps(vec![Ok(Rc::new(edited))]);
+    picker.on_button_up(KeyCode::Return);
+    assert!(picker.is_complete());
+    match picker.get_next_stage_params(&mut cursor_mem) {
+        SceneParams::AlgoSelection { variant, .. } => assert_eq!(variant, 0),
+        _ => panic!("picker didn't move on to algorithm selection"),
+    }
+
+    //a stale cursor from before the edit is clamped as well
+    cursor_mem.insert(VARIANT_CURSOR_ID, 5);
+    cursor_mem.insert(MAP_CURSOR_ID, 7);
+    let mut picker = MapPicker::new(&cursor_mem);
+    picker.set_maps(vec![Ok(Rc::new(parse_map("M0000\nS0,0\nE3,0\n", "map0").unwrap()))]);
+    picker.on_button_up(KeyCode::Return);
+    assert!(matches!(picker.get_next_stage_params(&mut cursor_mem), SceneParams::AlgoSelection { variant: 0, .. }));
+}
+
+#[test]
+fn jps_is_refused_on_maps_with_tile_costs() {
+    let jps = (0..Algo::len()).position(|idx| matches!(Algo::from_index(idx), Algo::Jps)).unwrap();
+    for (text, available) in [("M0000\nS0,0\nE3,0\n", true), ("M0300\nS0,0\nE3,0\n", false)] {
+        let mut picker = AlgoPicker::new(Rc::new(parse_map(text, "map0").unwrap()), 0, &HashMap::new());
+        for _ in 0..jps {
+            picker.on_button_down(KeyCode::Down);
+        }
+        picker.on_button_up(KeyCode::Return);
+        assert_eq!(picker.is_complete(), available, "JPS picked on {:?}", text);
+    }
+}
+
+#[test]
+fn editor_asks_before_unsaved_edits_are_discarded() {
+    let map = Rc::new(parse_map("M0000\nM0000\nS0,0\nE3,1\n", "map0").unwrap());
+    let mut editor = MapEditor::new(map, 0, &HashMap::new());
+    assert!(!editor.intercepts_key(KeyCode::R), "nothing to lose before editing");
+
+    editor.on_button_down(KeyCode::Right);
+    editor.on_button_down(KeyCode::Key9);
+    editor.on_button_down(KeyCode::Space);
+    for key in [KeyCode::R, KeyCode::Q, KeyCode::Escape] {
+        assert!(editor.intercepts_key(key), "{:?} discarded edits without asking", key);
+        assert!(!editor.intercepts_key(key), "{:?} asked twice", key);
+    }
+
+    //asking again once something else was pressed in between
+    editor.intercepts_key(KeyCode::R);
+    editor.on_button_down(KeyCode::Left);
+    assert!(editor.intercepts_key(KeyCode::R));
+    assert!(!editor.intercepts_key(KeyCode::Left));
+}